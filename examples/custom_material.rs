@@ -0,0 +1,81 @@
+//! Draws a shape with a custom `Material2d`,
+//! whose shader makes the vertex colors of the shape pulse over time.
+
+use bevy::{
+    color::palettes::css::*,
+    prelude::*,
+    render::render_resource::{AsBindGroup, ShaderRef},
+    sprite::{AlphaMode2d, Material2d, Material2dPlugin},
+};
+use bevy_prototype_lyon::prelude::*;
+
+const PULSE_SHADER_HANDLE: Handle<Shader> = Handle::weak_from_u128(0x1B2F_9C44_07A3_4E6D);
+
+const PULSE_SHADER: &str = r"
+#import bevy_sprite::{
+    mesh2d_vertex_output::VertexOutput,
+    mesh2d_view_bindings::globals,
+}
+
+@group(2) @binding(0) var<uniform> speed: f32;
+
+@fragment
+fn fragment(mesh: VertexOutput) -> @location(0) vec4<f32> {
+    let pulse = 0.5 + 0.5 * sin(globals.time * speed);
+    return vec4<f32>(mesh.color.rgb * pulse, mesh.color.a);
+}
+";
+
+#[derive(Asset, TypePath, AsBindGroup, Clone)]
+struct PulseMaterial {
+    #[uniform(0)]
+    speed: f32,
+}
+
+impl Material2d for PulseMaterial {
+    fn fragment_shader() -> ShaderRef {
+        PULSE_SHADER_HANDLE.into()
+    }
+
+    fn alpha_mode(&self) -> AlphaMode2d {
+        AlphaMode2d::Blend
+    }
+}
+
+fn main() {
+    App::new()
+        .add_plugins((
+            DefaultPlugins,
+            ShapePlugin,
+            Material2dPlugin::<PulseMaterial>::default(),
+            ShapeMaterialPlugin::<PulseMaterial>::default(),
+        ))
+        .add_systems(Startup, setup_system)
+        .run();
+}
+
+fn setup_system(
+    mut commands: Commands,
+    mut shaders: ResMut<Assets<Shader>>,
+    mut materials: ResMut<Assets<PulseMaterial>>,
+) {
+    shaders.insert(
+        &PULSE_SHADER_HANDLE,
+        Shader::from_wgsl(PULSE_SHADER, file!()),
+    );
+
+    let star = shapes::RegularPolygon {
+        sides: 5,
+        feature: shapes::RegularPolygonFeature::Radius(200.0),
+        ..shapes::RegularPolygon::default()
+    };
+
+    commands.spawn((Camera2d, Msaa::Sample4));
+    commands.spawn((
+        ShapeBuilder::with(&star)
+            .fill(GOLD)
+            .stroke((DARK_RED, 10.0))
+            .build(),
+        MeshMaterial2d(materials.add(PulseMaterial { speed: 3.0 })),
+    ));
+}
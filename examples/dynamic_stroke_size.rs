@@ -1,6 +1,6 @@
 //! Demonstrate surgical changes on the fields of `Shape` (path, fill, stroke).
 //! The triangle changes fill color; the hexagon changes stroke width; and
-//! the node positions of all shapes are extracted from the path to apply different
+//! the bounds of all shapes are read from `ShapeBounds` to apply different
 //! rotations on size.
 
 use bevy::{color::palettes::css::*, prelude::*};
@@ -22,22 +22,16 @@ struct HexagonShape;
 #[derive(Component)]
 struct TriangleShape;
 
-/// Walk Path to get the maximum x coordinate.
-fn get_max_x(shape: &Shape) -> f32 {
-    shape
-        .path
-        .iter()
-        .map(|p| p.to().x)
-        .chain(shape.path.iter().map(|p| p.from().x))
-        .fold(0f32, |acc, x| if x - acc > 1e-8 { x } else { acc })
-}
-
 /// Over time, rotate smaller shapes faster.
-fn rotate_shape_by_size(mut query: Query<(&mut Transform, &Shape)>, time: Res<Time>) {
+fn rotate_shape_by_size(mut query: Query<(&mut Transform, &ShapeBounds)>, time: Res<Time>) {
     let delta = time.delta_secs();
 
-    for (mut transform, shape) in query.iter_mut() {
-        transform.rotate(Quat::from_rotation_z(200.0 / get_max_x(shape) * delta));
+    for (mut transform, bounds) in query.iter_mut() {
+        // Bounds are empty until the shape is built.
+        let max_x = bounds.local().max.x;
+        if max_x > 0.0 {
+            transform.rotate(Quat::from_rotation_z(200.0 / max_x * delta));
+        }
     }
 }
 
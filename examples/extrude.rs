@@ -0,0 +1,77 @@
+//! Extrudes a badge with a star-shaped tunnel into a beveled 3D mesh.
+//!
+//! Run with `cargo run --example extrude --features 3d`.
+
+use bevy::{color::palettes::css::*, prelude::*};
+use bevy_prototype_lyon::prelude::*;
+
+fn main() {
+    App::new()
+        .add_plugins((DefaultPlugins, ShapePlugin))
+        .add_systems(Startup, setup_system)
+        .add_systems(Update, rotate_system)
+        .run();
+}
+
+#[derive(Component)]
+struct Badge;
+
+fn setup_system(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let badge = shapes::Circle {
+        radius: 2.0,
+        ..default()
+    };
+    let star = shapes::Polygon {
+        points: vec![
+            Vec2::new(0.0, 1.2),
+            Vec2::new(0.35, 0.45),
+            Vec2::new(1.15, 0.37),
+            Vec2::new(0.55, -0.15),
+            Vec2::new(0.7, -0.95),
+            Vec2::new(0.0, -0.55),
+            Vec2::new(-0.7, -0.95),
+            Vec2::new(-0.55, -0.15),
+            Vec2::new(-1.15, 0.37),
+            Vec2::new(-0.35, 0.45),
+        ],
+        closed: true,
+    };
+    let shape = ShapeBuilder::with(&badge)
+        .add(&star)
+        .fill(Fill::color(GOLD).with_options(FillOptions::even_odd().with_tolerance(0.01)))
+        .build();
+
+    let mesh = ExtrusionOptions::new(0.5)
+        .with_bevel(Bevel::round(0.08, 4))
+        .with_normals(ExtrusionNormals::Smooth)
+        .mesh(&shape);
+
+    commands.spawn((
+        Camera3d::default(),
+        Msaa::Sample4,
+        Transform::from_xyz(0.0, 1.0, 7.0).looking_at(Vec3::ZERO, Vec3::Y),
+    ));
+    commands.spawn((
+        DirectionalLight::default(),
+        Transform::from_xyz(2.0, 3.0, 4.0).looking_at(Vec3::ZERO, Vec3::Y),
+    ));
+    commands.spawn((
+        Mesh3d(meshes.add(mesh)),
+        MeshMaterial3d(materials.add(StandardMaterial {
+            metallic: 0.8,
+            perceptual_roughness: 0.3,
+            ..default()
+        })),
+        Badge,
+    ));
+}
+
+fn rotate_system(time: Res<Time>, mut query: Query<&mut Transform, With<Badge>>) {
+    for mut transform in &mut query {
+        transform.rotate_y(time.delta_secs() * 0.8);
+    }
+}
@@ -0,0 +1,62 @@
+//! Fits shapes with different origin conventions and sizes
+//! into the cells of a grid, with various fit modes and anchors.
+
+use bevy::{color::palettes::css::*, prelude::*, sprite::Anchor};
+use bevy_prototype_lyon::prelude::*;
+
+const CELL: f32 = 150.0;
+
+fn main() {
+    App::new()
+        .add_plugins((DefaultPlugins, ShapePlugin))
+        .add_systems(Startup, setup_system)
+        .run();
+}
+
+fn setup_system(mut commands: Commands) {
+    commands.spawn((Camera2d, Msaa::Sample4));
+
+    let circle = shapes::Circle {
+        radius: 10.0,
+        center: Vec2::new(300.0, 300.0),
+    };
+    let rect = shapes::Rectangle {
+        extents: Vec2::new(400.0, 100.0),
+        origin: RectangleOrigin::BottomLeft,
+        ..default()
+    };
+    let hexagon = shapes::RegularPolygon {
+        sides: 6,
+        ..default()
+    };
+
+    let cells = [
+        (FitMode::Contain, Anchor::Center),
+        (FitMode::Contain, Anchor::TopLeft),
+        (FitMode::Fill, Anchor::Center),
+        (FitMode::None, Anchor::BottomRight),
+    ];
+    for (row, (mode, anchor)) in cells.into_iter().enumerate() {
+        let y = (1.5 - row as f32) * CELL;
+        for (column, color) in [RED, GREEN, BLUE].into_iter().enumerate() {
+            let x = (column as f32 - 1.0) * CELL;
+            let target = Rect::from_center_size(Vec2::new(x, y), Vec2::splat(CELL * 0.8));
+            let builder = match column {
+                0 => ShapeBuilder::with(&circle),
+                1 => ShapeBuilder::with(&rect),
+                _ => ShapeBuilder::with(&hexagon),
+            };
+            commands.spawn(builder.fill(color).fit(target, mode, anchor).build());
+
+            let cell = shapes::Rectangle {
+                extents: target.size(),
+                origin: RectangleOrigin::CustomCenter(target.center()),
+                ..default()
+            };
+            commands.spawn((
+                ShapeBuilder::with(&cell).stroke((GRAY, 1.0)).build(),
+                Transform::from_xyz(0.0, 0.0, 1.0),
+            ));
+        }
+    }
+}
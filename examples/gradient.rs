@@ -0,0 +1,49 @@
+use bevy::{color::palettes::css::*, prelude::*};
+use bevy_prototype_lyon::prelude::*;
+
+fn main() {
+    App::new()
+        .add_plugins((DefaultPlugins, ShapePlugin))
+        .add_systems(Startup, setup_system)
+        .run();
+}
+
+fn setup_system(mut commands: Commands) {
+    let circle = shapes::Circle {
+        radius: 150.0,
+        center: Vec2::ZERO,
+    };
+    let fill = Gradient::radial(Vec2::new(-50.0, 50.0), 200.0)
+        .with_stop(0.0, YELLOW)
+        .with_stop(0.5, ORANGE)
+        .with_stop(1.0, DARK_RED);
+    let stroke = Gradient::conic(Vec2::ZERO, 0.0)
+        .with_stop(0.0, BLUE)
+        .with_stop(0.5, LIME)
+        .with_stop(1.0, BLUE);
+
+    let rect = shapes::Rectangle {
+        extents: Vec2::new(500.0, 60.0),
+        ..default()
+    };
+    let stripes = Gradient::linear(Vec2::ZERO, Vec2::new(50.0, 0.0))
+        .with_stop(0.0, WHITE)
+        .with_stop(1.0, BLACK)
+        .with_spread(SpreadMode::Reflect);
+
+    commands.spawn((Camera2d, Msaa::Sample4));
+    commands.spawn((
+        ShapeBuilder::with(&circle)
+            .fill(fill)
+            .stroke((stroke, 20.0))
+            .build(),
+        Transform::from_xyz(0.0, 60.0, 0.0),
+    ));
+    commands.spawn((
+        ShapeBuilder::with(&rect)
+            .fill(stripes)
+            .stroke((BLACK, 4.0))
+            .build(),
+        Transform::from_xyz(0.0, -200.0, 0.0),
+    ));
+}
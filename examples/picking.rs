@@ -0,0 +1,108 @@
+//! Highlights shapes under the cursor and logs clicks.
+//! Only the visible parts of the shapes can be picked:
+//! the hole of the ring and the gaps of the star are transparent to the
+//! pointer.
+//!
+//! Run with `cargo run --example picking --features picking`.
+
+use bevy::{color::palettes::css::*, prelude::*};
+use bevy_prototype_lyon::prelude::*;
+
+fn main() {
+    App::new()
+        .add_plugins((DefaultPlugins, ShapePlugin, ShapePickingPlugin))
+        .add_systems(Startup, setup_system)
+        .run();
+}
+
+/// Fill color of a shape when it is not hovered.
+#[derive(Component)]
+struct BaseColor(Color);
+
+fn setup_system(mut commands: Commands) {
+    commands.spawn((Camera2d, Msaa::Sample4));
+
+    let ring = shapes::Circle {
+        radius: 120.0,
+        center: Vec2::ZERO,
+    };
+    spawn_pickable(
+        &mut commands,
+        ShapeBuilder::with(&ring).stroke((TEAL, 40.0)).build(),
+        Transform::from_xyz(-200.0, 0.0, 0.0),
+    );
+
+    let star = ShapePath::new()
+        .move_to(Vec2::new(0.0, 150.0))
+        .line_to(Vec2::new(88.0, -121.0))
+        .line_to(Vec2::new(-143.0, 46.0))
+        .line_to(Vec2::new(143.0, 46.0))
+        .line_to(Vec2::new(-88.0, -121.0))
+        .close();
+    spawn_pickable(
+        &mut commands,
+        ShapeBuilder::with(&star)
+            .fill(
+                Fill::color(GOLD)
+                    .with_options(FillOptions::default().with_fill_rule(FillRule::EvenOdd)),
+            )
+            .build(),
+        Transform::from_xyz(200.0, 0.0, 0.0),
+    );
+
+    let square = shapes::Rectangle {
+        extents: Vec2::splat(120.0),
+        ..default()
+    };
+    spawn_pickable(
+        &mut commands,
+        ShapeBuilder::with(&square)
+            .fill(CRIMSON)
+            .stroke((BLACK, 8.0))
+            .build(),
+        Transform::from_xyz(0.0, 0.0, 1.0).with_rotation(Quat::from_rotation_z(0.4)),
+    );
+}
+
+fn spawn_pickable(commands: &mut Commands, shape: Shape, transform: Transform) {
+    let color = shape
+        .fill
+        .map_or_else(|| shape.stroke.unwrap().color, |fill| fill.color);
+    commands
+        .spawn((shape, transform, BaseColor(color)))
+        .observe(on_over)
+        .observe(on_out)
+        .observe(on_click);
+}
+
+fn on_over(trigger: Trigger<Pointer<Over>>, mut query: Query<&mut Shape>) {
+    if let Ok(mut shape) = query.get_mut(trigger.entity()) {
+        set_color(&mut shape, WHITE.into());
+    }
+}
+
+fn on_out(trigger: Trigger<Pointer<Out>>, mut query: Query<(&mut Shape, &BaseColor)>) {
+    if let Ok((mut shape, base_color)) = query.get_mut(trigger.entity()) {
+        set_color(&mut shape, base_color.0);
+    }
+}
+
+fn on_click(trigger: Trigger<Pointer<Click>>, hits: Res<ShapeHits>) {
+    let event = trigger.event();
+    let part = hits.part(event.pointer_id, event.hit.camera, trigger.entity());
+    if let (Some(position), Some(part)) = (event.hit.position, part) {
+        info!(
+            "Clicked the {part:?} of {} at {}",
+            trigger.entity(),
+            position.truncate()
+        );
+    }
+}
+
+fn set_color(shape: &mut Shape, color: Color) {
+    if let Some(fill) = &mut shape.fill {
+        fill.color = color;
+    } else if let Some(stroke) = &mut shape.stroke {
+        stroke.color = color;
+    }
+}
@@ -0,0 +1,107 @@
+//! Draws a ground marking, a wall decal and a billboard health bar
+//! in a lit 3D scene.
+//!
+//! Run with `cargo run --example shapes_3d --features 3d`.
+
+use bevy::{color::palettes::css::*, prelude::*};
+use bevy_prototype_lyon::prelude::*;
+
+fn main() {
+    App::new()
+        .add_plugins((DefaultPlugins, ShapePlugin))
+        .add_systems(Startup, setup_system)
+        .add_systems(Update, orbit_camera_system)
+        .run();
+}
+
+fn setup_system(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    commands.spawn((
+        Camera3d::default(),
+        Msaa::Sample4,
+        Transform::from_xyz(0.0, 4.0, 8.0).looking_at(Vec3::ZERO, Vec3::Y),
+    ));
+    commands.spawn((
+        PointLight {
+            shadows_enabled: true,
+            ..default()
+        },
+        Transform::from_xyz(3.0, 6.0, 3.0),
+    ));
+
+    commands.spawn((
+        Mesh3d(meshes.add(Plane3d::default().mesh().size(10.0, 10.0))),
+        MeshMaterial3d(materials.add(Color::from(DARK_OLIVEGREEN))),
+    ));
+    commands.spawn((
+        Mesh3d(meshes.add(Cuboid::from_length(1.0))),
+        MeshMaterial3d(materials.add(Color::from(SILVER))),
+        Transform::from_xyz(0.0, 0.5, 0.0),
+    ));
+
+    let ring = shapes::Circle {
+        radius: 1.4,
+        ..default()
+    };
+    commands.spawn((
+        Shape3d::new(
+            ShapeBuilder::with(&ring).stroke((YELLOW, 0.15)).build(),
+            ShapePlane::XZ,
+        ),
+        Transform::from_xyz(0.0, 0.01, 0.0),
+    ));
+
+    let arrow = shapes::Polygon {
+        points: vec![
+            Vec2::new(-0.6, -0.2),
+            Vec2::new(0.2, -0.2),
+            Vec2::new(0.2, -0.5),
+            Vec2::new(0.7, 0.0),
+            Vec2::new(0.2, 0.5),
+            Vec2::new(0.2, 0.2),
+            Vec2::new(-0.6, 0.2),
+        ],
+        closed: true,
+    };
+    commands.spawn((
+        Shape3d::new(
+            ShapeBuilder::with(&arrow)
+                .fill(ORANGE_RED)
+                .stroke((WHITE, 0.05))
+                .build(),
+            ShapePlane::XY,
+        ),
+        Transform::from_xyz(-0.2, 0.5, 0.51),
+    ));
+
+    let frame = shapes::Rectangle {
+        extents: Vec2::new(1.2, 0.24),
+        ..default()
+    };
+    let health = shapes::Rectangle {
+        extents: Vec2::new(0.8, 0.16),
+        origin: RectangleOrigin::CustomCenter(Vec2::new(-0.16, 0.0)),
+        ..default()
+    };
+    commands.spawn((
+        Shape3d::from(ShapeBuilder::with(&frame).stroke((BLACK, 0.04)).build()),
+        Billboard::Screen,
+        Transform::from_xyz(0.0, 1.5, 0.0),
+    ));
+    commands.spawn((
+        Shape3d::from(ShapeBuilder::with(&health).fill(LIME).build()),
+        Billboard::Screen,
+        Transform::from_xyz(0.0, 1.5, 0.0),
+    ));
+}
+
+fn orbit_camera_system(time: Res<Time>, mut query: Query<&mut Transform, With<Camera3d>>) {
+    for mut transform in &mut query {
+        let angle = time.elapsed_secs() * 0.3;
+        *transform = Transform::from_xyz(8.0 * angle.sin(), 4.0, 8.0 * angle.cos())
+            .looking_at(Vec3::ZERO, Vec3::Y);
+    }
+}
@@ -0,0 +1,88 @@
+//! Fills a polygon with a procedurally generated texture,
+//! repeated every 64 units,
+//! and draws a road whose texture runs along its stroke.
+
+use bevy::{
+    color::palettes::css::*,
+    image::{ImageAddressMode, ImageSampler, ImageSamplerDescriptor},
+    prelude::*,
+    render::{
+        render_asset::RenderAssetUsages,
+        render_resource::{Extent3d, TextureDimension, TextureFormat},
+    },
+};
+use bevy_prototype_lyon::prelude::*;
+
+fn main() {
+    App::new()
+        .add_plugins((DefaultPlugins, ShapePlugin))
+        .add_systems(Startup, setup_system)
+        .run();
+}
+
+fn setup_system(
+    mut commands: Commands,
+    mut images: ResMut<Assets<Image>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    let terrain = shapes::Polygon {
+        points: vec![
+            Vec2::new(-400.0, -250.0),
+            Vec2::new(-400.0, 50.0),
+            Vec2::new(-250.0, 120.0),
+            Vec2::new(-50.0, 20.0),
+            Vec2::new(150.0, 150.0),
+            Vec2::new(400.0, 60.0),
+            Vec2::new(400.0, -250.0),
+        ],
+        closed: true,
+    };
+
+    let road = ShapePath::new()
+        .move_to(Vec2::new(-350.0, 250.0))
+        .cubic_bezier_to(
+            Vec2::new(-100.0, 400.0),
+            Vec2::new(50.0, 100.0),
+            Vec2::new(350.0, 250.0),
+        );
+
+    let material = materials.add(ColorMaterial::from(images.add(checker_image())));
+
+    commands.spawn((Camera2d, Msaa::Sample4));
+    commands.spawn((
+        ShapeBuilder::with(&terrain)
+            .fill(Fill::color(WHITE).with_uv(UvMapping::Tiled(Vec2::splat(64.0))))
+            .stroke((DARK_GREEN, 8.0))
+            .build(),
+        MeshMaterial2d(material.clone()),
+    ));
+    commands.spawn((
+        ShapeBuilder::with(&road)
+            .stroke(Stroke::new(WHITE, 40.0).with_uv(StrokeUvMapping::Tiled(80.0)))
+            .build(),
+        MeshMaterial2d(material),
+    ));
+}
+
+/// Creates a 2x2 checkerboard texture that repeats.
+fn checker_image() -> Image {
+    let light = [120, 200, 80, 255];
+    let dark = [60, 140, 40, 255];
+    let mut image = Image::new(
+        Extent3d {
+            width: 2,
+            height: 2,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        [light, dark, dark, light].concat(),
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::RENDER_WORLD,
+    );
+    image.sampler = ImageSampler::Descriptor(ImageSamplerDescriptor {
+        address_mode_u: ImageAddressMode::Repeat,
+        address_mode_v: ImageAddressMode::Repeat,
+        ..ImageSamplerDescriptor::nearest()
+    });
+    image
+}
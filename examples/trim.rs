@@ -0,0 +1,74 @@
+//! Animates the `Trim` of strokes.
+//! The circle is drawn on and erased in a loop, while a segment of constant
+//! length runs around the square, wrapping across the start of its path.
+
+use bevy::{color::palettes::css::*, prelude::*};
+use bevy_prototype_lyon::prelude::*;
+
+fn main() {
+    App::new()
+        .add_plugins((DefaultPlugins, ShapePlugin))
+        .add_systems(Startup, setup_system)
+        .add_systems(Update, (draw_on_system, chase_system))
+        .run();
+}
+
+#[derive(Component)]
+struct DrawOn;
+
+#[derive(Component)]
+struct Chase;
+
+/// Draws the stroke from its start, then erases it from its start.
+fn draw_on_system(mut query: Query<&mut Shape, With<DrawOn>>, time: Res<Time>) {
+    let phase = time.elapsed_secs() % 4.0 / 2.0;
+    let trim = if phase < 1.0 {
+        Trim::new(0.0, phase)
+    } else {
+        Trim::new(phase - 1.0, 1.0)
+    };
+
+    for mut shape in &mut query {
+        if let Some(stroke) = &mut shape.stroke {
+            stroke.trim = trim;
+        }
+    }
+}
+
+/// Moves a quarter of the stroke around the path.
+fn chase_system(mut query: Query<&mut Shape, With<Chase>>, time: Res<Time>) {
+    let trim = Trim::new(0.0, 0.25).with_offset(time.elapsed_secs() * 0.3);
+
+    for mut shape in &mut query {
+        if let Some(stroke) = &mut shape.stroke {
+            stroke.trim = trim;
+        }
+    }
+}
+
+fn setup_system(mut commands: Commands) {
+    let circle = shapes::Circle {
+        radius: 150.0,
+        ..default()
+    };
+    let square = shapes::Rectangle {
+        extents: Vec2::splat(250.0),
+        ..default()
+    };
+
+    commands.spawn((Camera2d, Msaa::Sample4));
+    commands.spawn((
+        ShapeBuilder::with(&circle)
+            .stroke(Stroke::new(DARK_CYAN, 16.0))
+            .build(),
+        Transform::from_xyz(-200.0, 0.0, 0.0),
+        DrawOn,
+    ));
+    commands.spawn((
+        ShapeBuilder::with(&square)
+            .stroke(Stroke::new(ORANGE, 16.0))
+            .build(),
+        Transform::from_xyz(200.0, 0.0, 0.0),
+        Chase,
+    ));
+}
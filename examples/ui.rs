@@ -0,0 +1,121 @@
+//! Draws a panel with rounded corners, an icon and a radial progress bar
+//! as `bevy_ui` nodes.
+//!
+//! Run with `cargo run --example ui --features ui`.
+
+use std::f32::consts::TAU;
+
+use bevy::{color::palettes::css::*, prelude::*};
+use bevy_prototype_lyon::prelude::*;
+
+const PROGRESS_RADIUS: f32 = 40.0;
+const PROGRESS_WIDTH: f32 = 12.0;
+
+fn main() {
+    App::new()
+        .add_plugins((DefaultPlugins, ShapePlugin))
+        .add_systems(Startup, setup_system)
+        .add_systems(Update, progress_system)
+        .run();
+}
+
+#[derive(Component)]
+struct Progress;
+
+fn setup_system(mut commands: Commands) {
+    commands.spawn(Camera2d);
+
+    let panel = ShapeNode::sized(|size| {
+        let rect = shapes::Rectangle {
+            // Keep the stroke inside the node.
+            extents: size - 4.0,
+            radii: Some(BorderRadii {
+                top_left: 32.0,
+                top_right: 8.0,
+                bottom_left: 8.0,
+                bottom_right: 32.0,
+            }),
+            ..default()
+        };
+        ShapeBuilder::with(&rect)
+            .fill(Color::srgb(0.12, 0.14, 0.2))
+            .stroke((STEEL_BLUE, 4.0))
+            .build()
+    });
+
+    let icon = shapes::RegularPolygon {
+        sides: 6,
+        feature: RegularPolygonFeature::Radius(1.0),
+        ..default()
+    };
+
+    commands
+        .spawn(Node {
+            width: Val::Percent(100.0),
+            height: Val::Percent(100.0),
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            ..default()
+        })
+        .with_children(|parent| {
+            parent
+                .spawn((
+                    panel,
+                    Node {
+                        width: Val::Px(360.0),
+                        height: Val::Px(200.0),
+                        justify_content: JustifyContent::SpaceEvenly,
+                        align_items: AlignItems::Center,
+                        ..default()
+                    },
+                ))
+                .with_children(|parent| {
+                    parent.spawn((
+                        ShapeNode::new(
+                            ShapeBuilder::with(&icon)
+                                .fill(
+                                    Gradient::radial(Vec2::ZERO, 1.0)
+                                        .with_stop(0.0, GOLD)
+                                        .with_stop(1.0, ORANGE_RED),
+                                )
+                                .build(),
+                        ),
+                        Node {
+                            width: Val::Px(96.0),
+                            height: Val::Px(96.0),
+                            ..default()
+                        },
+                    ));
+                    parent.spawn((
+                        ShapeNode::new(progress_arc(0.0)).with_view_box(
+                            Rect::from_center_half_size(
+                                Vec2::ZERO,
+                                Vec2::splat(PROGRESS_RADIUS + PROGRESS_WIDTH / 2.0),
+                            ),
+                        ),
+                        Progress,
+                    ));
+                });
+        });
+}
+
+fn progress_arc(progress: f32) -> Shape {
+    let arc = ShapePath::new()
+        .move_to(Vec2::new(0.0, PROGRESS_RADIUS))
+        .arc(
+            Vec2::ZERO,
+            Vec2::splat(PROGRESS_RADIUS),
+            -progress * TAU,
+            0.0,
+        );
+    let mut stroke = Stroke::new(LIME, PROGRESS_WIDTH);
+    stroke.options = stroke.options.with_line_cap(LineCap::Round);
+    ShapeBuilder::with(&arc).stroke(stroke).build()
+}
+
+fn progress_system(time: Res<Time>, mut query: Query<&mut ShapeNode, With<Progress>>) {
+    let progress = (time.elapsed_secs() * 0.25).fract();
+    for mut node in &mut query {
+        node.shape = progress_arc(progress);
+    }
+}
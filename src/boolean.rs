@@ -0,0 +1,567 @@
+//! Boolean operations between paths.
+//!
+//! Fills of [`Shape`]s and [`ShapePath`]s can be combined
+//! with a [`BooleanOp`] into a [`BooleanPath`],
+//! which can be drawn with [`ShapeBuilder`](crate::geometry::ShapeBuilder)
+//! like any other [`Geometry`].
+//!
+//! Curves are flattened to the tolerance of the operands,
+//! so results are made of straight lines.
+//! Outer boundaries of the results wind counter-clockwise
+//! and holes clockwise, so they can be filled with any [`FillRule`].
+
+use bevy::{
+    math::DVec2,
+    utils::{HashMap, HashSet},
+};
+use lyon_tessellation::{
+    math::Point,
+    path::{iterator::PathIterator, path::Builder, Path, PathEvent},
+    FillOptions, FillRule,
+};
+
+use crate::{entity::Shape, geometry::Geometry, path::ShapePath};
+
+/// Boolean operation between two filled areas.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BooleanOp {
+    /// Area covered by any of the operands.
+    Union,
+    /// Area covered by both operands.
+    Intersection,
+    /// Area covered by the first operand but not by the second.
+    Difference,
+    /// Area covered by exactly one of the operands.
+    Xor,
+}
+
+impl BooleanOp {
+    fn apply(self, a: bool, b: bool) -> bool {
+        match self {
+            Self::Union => a || b,
+            Self::Intersection => a && b,
+            Self::Difference => a && !b,
+            Self::Xor => a != b,
+        }
+    }
+}
+
+/// Path resulting from a [`BooleanOp`].
+///
+/// # Example
+///
+/// ```
+/// use bevy::{color::palettes::css::*, prelude::*};
+/// use bevy_prototype_lyon::prelude::*;
+///
+/// let rect = shapes::Rectangle {
+///     extents: Vec2::new(200.0, 100.0),
+///     ..default()
+/// };
+/// let circle = shapes::Circle {
+///     radius: 40.0,
+///     center: Vec2::new(100.0, 0.0),
+/// };
+///
+/// let rect = ShapeBuilder::with(&rect).fill(RED).build();
+/// let circle = ShapeBuilder::with(&circle).fill(RED).build();
+/// let notched = rect.difference(&circle);
+///
+/// let shape = ShapeBuilder::with(&notched).fill(DARK_CYAN).build();
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct BooleanPath {
+    path: Path,
+}
+
+impl BooleanPath {
+    /// Applies `op` to the fills of paths `a` and `b`,
+    /// determined by their fill rules.
+    ///
+    /// Curves are flattened with `tolerance`.
+    #[must_use]
+    pub fn new(
+        a: &Path,
+        a_fill_rule: FillRule,
+        op: BooleanOp,
+        b: &Path,
+        b_fill_rule: FillRule,
+        tolerance: f32,
+    ) -> Self {
+        let a = Operand::new(a, a_fill_rule, tolerance);
+        let b = Operand::new(b, b_fill_rule, tolerance);
+        Self {
+            path: overlay(&a, op, &b, f64::from(tolerance)),
+        }
+    }
+
+    /// Wraps a path that is already the result of an overlay.
+    pub(crate) const fn from_overlay(path: Path) -> Self {
+        Self { path }
+    }
+
+    /// Returns the resulting lyon `Path`.
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Converts into the resulting lyon `Path`.
+    #[must_use]
+    pub fn into_path(self) -> Path {
+        self.path
+    }
+}
+
+impl Geometry<Builder> for BooleanPath {
+    fn add_geometry(&self, b: &mut Builder) {
+        b.extend_from_paths(&[self.path.as_slice()]);
+    }
+}
+
+impl Shape {
+    /// Applies `op` to the fill of this shape and the fill of `other`,
+    /// according to their fill rules.
+    ///
+    /// Both shapes are taken in the same space,
+    /// and shapes without fill use the default fill rule of [`FillOptions`].
+    #[must_use]
+    pub fn boolean(&self, op: BooleanOp, other: &Self) -> BooleanPath {
+        let fill_rule = |shape: &Self| {
+            shape.fill.map_or(FillOptions::DEFAULT_FILL_RULE, |fill| {
+                fill.options.fill_rule
+            })
+        };
+        BooleanPath::new(
+            &self.path,
+            fill_rule(self),
+            op,
+            &other.path,
+            fill_rule(other),
+            self.tolerance().min(other.tolerance()),
+        )
+    }
+
+    /// Returns the area covered by this shape or by `other`.
+    ///
+    /// See [`Shape::boolean`].
+    #[must_use]
+    pub fn union(&self, other: &Self) -> BooleanPath {
+        self.boolean(BooleanOp::Union, other)
+    }
+
+    /// Returns the area covered by both this shape and `other`.
+    ///
+    /// See [`Shape::boolean`].
+    #[must_use]
+    pub fn intersection(&self, other: &Self) -> BooleanPath {
+        self.boolean(BooleanOp::Intersection, other)
+    }
+
+    /// Returns the area covered by this shape but not by `other`.
+    ///
+    /// See [`Shape::boolean`].
+    #[must_use]
+    pub fn difference(&self, other: &Self) -> BooleanPath {
+        self.boolean(BooleanOp::Difference, other)
+    }
+
+    /// Returns the area covered by either this shape or `other`, but not
+    /// both.
+    ///
+    /// See [`Shape::boolean`].
+    #[must_use]
+    pub fn xor(&self, other: &Self) -> BooleanPath {
+        self.boolean(BooleanOp::Xor, other)
+    }
+}
+
+impl ShapePath {
+    /// Applies `op` to the area enclosed by this path and the area enclosed
+    /// by `other`, both determined by `fill_rule`.
+    ///
+    /// Curves are flattened with the default tolerance of [`FillOptions`].
+    #[must_use]
+    pub fn boolean(&self, op: BooleanOp, other: &Self, fill_rule: FillRule) -> BooleanPath {
+        BooleanPath::new(
+            &self.build(),
+            fill_rule,
+            op,
+            &other.build(),
+            fill_rule,
+            FillOptions::DEFAULT_TOLERANCE,
+        )
+    }
+}
+
+/// Operand of a boolean operation: the edges of its flattened, closed
+/// outline, and its fill rule.
+pub(crate) struct Operand {
+    edges: Vec<(DVec2, DVec2)>,
+    fill_rule: FillRule,
+}
+
+impl Operand {
+    /// Creates an operand from closed polygons.
+    pub(crate) fn from_polygons(polygons: &[Vec<DVec2>], fill_rule: FillRule) -> Self {
+        let edges = polygons
+            .iter()
+            .flat_map(|polygon| {
+                polygon
+                    .iter()
+                    .zip(polygon.iter().cycle().skip(1))
+                    .map(|(from, to)| (*from, *to))
+            })
+            .filter(|(from, to)| from != to)
+            .collect();
+        Self { edges, fill_rule }
+    }
+
+    pub(crate) fn new(path: &Path, fill_rule: FillRule, tolerance: f32) -> Self {
+        let to_dvec2 = |p: Point| DVec2::new(f64::from(p.x), f64::from(p.y));
+        let edges = path
+            .iter()
+            .flattened(tolerance)
+            .filter_map(|event| match event {
+                PathEvent::Line { from, to } => Some((to_dvec2(from), to_dvec2(to))),
+                // Fills close all subpaths.
+                PathEvent::End { last, first, .. } => Some((to_dvec2(last), to_dvec2(first))),
+                _ => None,
+            })
+            .filter(|(from, to)| from != to)
+            .collect();
+        Self { edges, fill_rule }
+    }
+
+    /// Returns `true` if `point` is inside the fill.
+    fn contains(&self, point: DVec2) -> bool {
+        let winding: i32 = self
+            .edges
+            .iter()
+            .map(|&(from, to)| {
+                let side = (to - from).perp_dot(point - from);
+                if from.y <= point.y {
+                    i32::from(to.y > point.y && side > 0.0)
+                } else {
+                    -i32::from(to.y <= point.y && side < 0.0)
+                }
+            })
+            .sum();
+        match self.fill_rule {
+            FillRule::EvenOdd => winding % 2 != 0,
+            FillRule::NonZero => winding != 0,
+        }
+    }
+}
+
+/// Computes the outline of the result of `op`.
+///
+/// All edges are split where they cross,
+/// then the edges separating the inside of the result from its outside
+/// are kept, with the inside on their left, and chained into contours.
+///
+/// Takes O(E²) time for E edges:
+/// [`split_points`] tests every pair of edges,
+/// and each split edge is classified by two O(E) inside tests.
+pub(crate) fn overlay(a: &Operand, op: BooleanOp, b: &Operand, tolerance: f64) -> Path {
+    // Distance under which points are merged.
+    let snap = tolerance * 1e-3;
+    let edges: Vec<_> = a.edges.iter().chain(&b.edges).copied().collect();
+    let splits = split_points(&edges, snap);
+
+    let mut seen = HashSet::new();
+    let mut boundary = Vec::new();
+    for (&(from, to), mut points) in edges.iter().zip(splits) {
+        points.sort_by(|(s, _), (t, _)| s.total_cmp(t));
+        let vertices = std::iter::once(from)
+            .chain(points.into_iter().map(|(_, point)| point))
+            .chain(std::iter::once(to));
+        let vertices: Vec<_> = vertices.collect();
+        for pair in vertices.windows(2) {
+            let (start, end) = (pair[0], pair[1]);
+            let length = start.distance(end);
+            if length < snap {
+                continue;
+            }
+            // Coincident edges separate the same areas.
+            let (key_start, key_end) = (quantize(start, snap), quantize(end, snap));
+            if !seen.insert((key_start.min(key_end), key_start.max(key_end))) {
+                continue;
+            }
+            let mid = (start + end) / 2.0;
+            let offset = (end - start).perp() / length * (length * 1e-3).min(tolerance * 1e-2);
+            let inside = |point| op.apply(a.contains(point), b.contains(point));
+            match (inside(mid + offset), inside(mid - offset)) {
+                (true, false) => boundary.push((start, end)),
+                (false, true) => boundary.push((end, start)),
+                _ => {}
+            }
+        }
+    }
+    chain(&boundary, snap)
+}
+
+/// Returns, for each edge, the parameters and positions of the points where
+/// it must be split, excluding its endpoints.
+fn split_points(edges: &[(DVec2, DVec2)], snap: f64) -> Vec<Vec<(f64, DVec2)>> {
+    let mut splits = vec![Vec::new(); edges.len()];
+    for (i, &(a0, a1)) in edges.iter().enumerate() {
+        for (j, &(b0, b1)) in edges.iter().enumerate().skip(i + 1) {
+            let (a_min, a_max) = (a0.min(a1), a0.max(a1));
+            let (b_min, b_max) = (b0.min(b1), b0.max(b1));
+            if a_min.cmpgt(b_max + snap).any() || b_min.cmpgt(a_max + snap).any() {
+                continue;
+            }
+            for (edge, t, point) in crossings((a0, a1), (b0, b1), snap) {
+                splits[if edge == 0 { i } else { j }].push((t, point));
+            }
+        }
+    }
+    splits
+}
+
+/// Returns the points where edge `a` (`0`) and edge `b` (`1`) must be split
+/// where they cross or touch, along with their parameter on the split edge.
+///
+/// Endpoints are reused when close enough,
+/// so split edges share their vertices exactly.
+fn crossings(
+    (a0, a1): (DVec2, DVec2),
+    (b0, b1): (DVec2, DVec2),
+    snap: f64,
+) -> Vec<(usize, f64, DVec2)> {
+    let (da, db) = (a1 - a0, b1 - b0);
+    let mut result = Vec::new();
+    // Splits `edge` at `point`, if it lies inside it.
+    let mut split = |edge: usize, (from, to): (DVec2, DVec2), point: DVec2| {
+        let direction = to - from;
+        let t = (point - from).dot(direction) / direction.length_squared();
+        let inside = t > 0.0 && t < 1.0;
+        if inside && point.distance(from) > snap && point.distance(to) > snap {
+            result.push((edge, t, point));
+        }
+    };
+
+    let denominator = da.perp_dot(db);
+    if denominator.abs() <= f64::EPSILON * da.length() * db.length() {
+        // Parallel edges only need splitting if they are collinear.
+        if distance_to_line(b0, a0, a1) <= snap {
+            split(0, (a0, a1), b0);
+            split(0, (a0, a1), b1);
+            split(1, (b0, b1), a0);
+            split(1, (b0, b1), a1);
+        }
+        return result;
+    }
+    let ta = (b0 - a0).perp_dot(db) / denominator;
+    let tb = (b0 - a0).perp_dot(da) / denominator;
+    let margin_a = snap / da.length();
+    let margin_b = snap / db.length();
+    if !(-margin_a..=1.0 + margin_a).contains(&ta) || !(-margin_b..=1.0 + margin_b).contains(&tb) {
+        return result;
+    }
+    let point = [a0, a1, b0, b1]
+        .into_iter()
+        .find(|endpoint| endpoint.distance(a0 + da * ta) <= snap)
+        .unwrap_or(a0 + da * ta);
+    split(0, (a0, a1), point);
+    split(1, (b0, b1), point);
+    result
+}
+
+fn distance_to_line(point: DVec2, from: DVec2, to: DVec2) -> f64 {
+    (to - from).perp_dot(point - from).abs() / from.distance(to)
+}
+
+fn quantize(point: DVec2, snap: f64) -> (i64, i64) {
+    (
+        (point.x / snap).round() as i64,
+        (point.y / snap).round() as i64,
+    )
+}
+
+/// Chains oriented edges into closed contours.
+fn chain(edges: &[(DVec2, DVec2)], snap: f64) -> Path {
+    let mut outgoing: HashMap<(i64, i64), Vec<usize>> = HashMap::new();
+    for (i, (from, _)) in edges.iter().enumerate() {
+        outgoing.entry(quantize(*from, snap)).or_default().push(i);
+    }
+    // Looks up the neighboring cells too,
+    // in case merged points were quantized differently.
+    let mut take_next = |point: DVec2| {
+        let (x, y) = quantize(point, snap);
+        (-1..=1)
+            .flat_map(|dx| (-1..=1).map(move |dy| (x + dx, y + dy)))
+            .find_map(|key| outgoing.get_mut(&key).and_then(Vec::pop))
+    };
+
+    let to_point = |p: DVec2| Point::new(p.x as f32, p.y as f32);
+    let mut builder = Path::builder();
+    let mut used = vec![false; edges.len()];
+    for first in 0..edges.len() {
+        if used[first] {
+            continue;
+        }
+        let start = edges[first].0;
+        builder.begin(to_point(start));
+        let mut current = first;
+        loop {
+            used[current] = true;
+            let end = edges[current].1;
+            if end.distance(start) <= snap {
+                break;
+            }
+            builder.line_to(to_point(end));
+            match std::iter::from_fn(|| take_next(end)).find(|&next| !used[next]) {
+                Some(next) => current = next,
+                None => break,
+            }
+        }
+        builder.end(true);
+    }
+    builder.build()
+}
+
+#[cfg(test)]
+mod tests {
+    use lyon_algorithms::area::approximate_signed_area;
+    use lyon_tessellation::{math::point, path::PathEvent};
+
+    use super::*;
+
+    fn square(min: (f32, f32), max: (f32, f32)) -> Path {
+        let mut builder = Path::builder();
+        builder.begin(point(min.0, min.1));
+        builder.line_to(point(max.0, min.1));
+        builder.line_to(point(max.0, max.1));
+        builder.line_to(point(min.0, max.1));
+        builder.close();
+        builder.build()
+    }
+
+    fn apply(a: &Path, op: BooleanOp, b: &Path) -> Path {
+        BooleanPath::new(a, FillRule::NonZero, op, b, FillRule::NonZero, 0.01).into_path()
+    }
+
+    /// Returns the signed area of every subpath of `path`.
+    fn subpath_areas(path: &Path) -> Vec<f32> {
+        let mut areas = Vec::new();
+        let mut events = Vec::new();
+        for event in path {
+            events.push(event);
+            if let PathEvent::End { .. } = event {
+                areas.push(approximate_signed_area(0.01, std::mem::take(&mut events)));
+            }
+        }
+        areas
+    }
+
+    fn area(path: &Path) -> f32 {
+        subpath_areas(path).iter().sum()
+    }
+
+    fn assert_close(actual: f32, expected: f32) {
+        assert!(
+            (actual - expected).abs() < 1e-3,
+            "expected {expected}, got {actual}"
+        );
+    }
+
+    #[test]
+    fn overlapping_squares() {
+        let a = square((0.0, 0.0), (2.0, 2.0));
+        let b = square((1.0, 1.0), (3.0, 3.0));
+        assert_close(area(&apply(&a, BooleanOp::Union, &b)), 7.0);
+        assert_close(area(&apply(&a, BooleanOp::Intersection, &b)), 1.0);
+        assert_close(area(&apply(&a, BooleanOp::Difference, &b)), 3.0);
+        assert_close(area(&apply(&a, BooleanOp::Xor, &b)), 6.0);
+    }
+
+    #[test]
+    fn outer_boundaries_wind_counter_clockwise() {
+        let a = square((0.0, 0.0), (2.0, 2.0));
+        let b = square((1.0, 1.0), (3.0, 3.0));
+        for op in [
+            BooleanOp::Union,
+            BooleanOp::Intersection,
+            BooleanOp::Difference,
+        ] {
+            let areas = subpath_areas(&apply(&a, op, &b));
+            assert_eq!(areas.len(), 1, "{op:?}");
+            assert!(areas[0] > 0.0, "{op:?}");
+        }
+        // The two parts of the xor touch at the corners of the overlap,
+        // which becomes a hole of their common outline.
+        let mut areas = subpath_areas(&apply(&a, BooleanOp::Xor, &b));
+        areas.sort_by(f32::total_cmp);
+        assert_eq!(areas.len(), 2);
+        assert_close(areas[0], -1.0);
+        assert_close(areas[1], 7.0);
+    }
+
+    #[test]
+    fn holes_wind_clockwise() {
+        let outer = square((0.0, 0.0), (4.0, 4.0));
+        let inner = square((1.0, 1.0), (3.0, 3.0));
+        let ring = apply(&outer, BooleanOp::Difference, &inner);
+        let mut areas = subpath_areas(&ring);
+        areas.sort_by(f32::total_cmp);
+        assert_eq!(areas.len(), 2);
+        assert_close(areas[0], -4.0);
+        assert_close(areas[1], 16.0);
+
+        // The hole is kept when the ring is combined with a disjoint shape.
+        let other = square((5.0, 0.0), (6.0, 1.0));
+        assert_close(area(&apply(&ring, BooleanOp::Union, &other)), 13.0);
+        // Filling the hole restores the outer square.
+        let filled = apply(&ring, BooleanOp::Union, &inner);
+        assert_close(area(&filled), 16.0);
+        assert_eq!(subpath_areas(&filled).len(), 1);
+    }
+
+    #[test]
+    fn even_odd_operands() {
+        // Both squares in one path: the inner one is a hole with even-odd.
+        let mut builder = Path::builder();
+        builder.extend_from_paths(&[
+            square((0.0, 0.0), (4.0, 4.0)).as_slice(),
+            square((1.0, 1.0), (3.0, 3.0)).as_slice(),
+        ]);
+        let nested = builder.build();
+        let empty = Path::new();
+        let even_odd = BooleanPath::new(
+            &nested,
+            FillRule::EvenOdd,
+            BooleanOp::Union,
+            &empty,
+            FillRule::EvenOdd,
+            0.01,
+        );
+        assert_close(area(even_odd.path()), 12.0);
+        assert_close(area(&apply(&nested, BooleanOp::Union, &empty)), 16.0);
+    }
+
+    #[test]
+    fn touching_edges() {
+        let a = square((0.0, 0.0), (1.0, 1.0));
+        let b = square((1.0, 0.0), (2.0, 1.0));
+        let union = apply(&a, BooleanOp::Union, &b);
+        assert_close(area(&union), 2.0);
+        assert_eq!(subpath_areas(&union).len(), 1);
+        assert_close(area(&apply(&a, BooleanOp::Intersection, &b)), 0.0);
+        assert_close(area(&apply(&a, BooleanOp::Difference, &b)), 1.0);
+        assert_close(area(&apply(&a, BooleanOp::Xor, &b)), 2.0);
+    }
+
+    #[test]
+    fn empty_operands() {
+        let a = square((0.0, 0.0), (1.0, 1.0));
+        let empty = Path::new();
+        assert_close(area(&apply(&a, BooleanOp::Union, &empty)), 1.0);
+        assert_close(area(&apply(&empty, BooleanOp::Union, &a)), 1.0);
+        assert_close(area(&apply(&a, BooleanOp::Difference, &empty)), 1.0);
+        assert_eq!(apply(&a, BooleanOp::Intersection, &empty).iter().count(), 0);
+        assert_eq!(apply(&empty, BooleanOp::Difference, &a).iter().count(), 0);
+        assert_eq!(apply(&empty, BooleanOp::Xor, &empty).iter().count(), 0);
+    }
+}
@@ -0,0 +1,172 @@
+//! Bounding boxes of shapes, kept up to date by the plugin.
+
+use bevy::{
+    prelude::*,
+    render::mesh::{Mesh2d, VertexAttributeValues},
+};
+use lyon_algorithms::aabb::{bounding_box, fast_bounding_box};
+use lyon_tessellation::{LineCap, LineJoin, StrokeOptions};
+
+use crate::{entity::Shape, utils::ToRect};
+
+/// Defines how the bounds of a shape are computed.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum BoundsMode {
+    /// The bounds enclose the vertices of the shape mesh,
+    /// so they are tight around both the fill and the stroke,
+    /// within the tessellation tolerance.
+    #[default]
+    Exact,
+    /// The bounds enclose the control points of the path,
+    /// enlarged by the maximum distance the stroke can reach from the path.
+    ///
+    /// They are cheaper to compute,
+    /// but may be larger than the shape.
+    Fast,
+}
+
+/// `Component` holding the axis-aligned bounding box of a [`Shape`],
+/// including its stroke.
+///
+/// It is updated by the [`ShapePlugin`](crate::plugin::ShapePlugin)
+/// whenever the shape, its transform or the bounds mode change,
+/// in the [`BuildShapes`](crate::plugin::BuildShapes) system set.
+///
+/// # Example
+///
+/// ```
+/// use bevy::prelude::*;
+/// use bevy_prototype_lyon::prelude::*;
+///
+/// /// Despawns the shapes that left the area between -1000 and 1000.
+/// fn despawn_system(mut commands: Commands, query: Query<(Entity, &ShapeBounds)>) {
+///     let area = Rect::new(-1000.0, -1000.0, 1000.0, 1000.0);
+///     for (entity, bounds) in &query {
+///         if area.intersect(bounds.world()).is_empty() {
+///             commands.entity(entity).despawn();
+///         }
+///     }
+/// }
+/// ```
+#[derive(Component, Debug, Default, Clone, Copy, PartialEq)]
+pub struct ShapeBounds {
+    /// How the bounds are computed.
+    pub mode: BoundsMode,
+    local: Rect,
+    world: Rect,
+}
+
+impl ShapeBounds {
+    /// Creates a component whose bounds are computed with `mode`.
+    #[must_use]
+    pub fn new(mode: BoundsMode) -> Self {
+        Self { mode, ..default() }
+    }
+
+    /// Returns the bounds in the local space of the shape.
+    #[must_use]
+    pub fn local(&self) -> Rect {
+        self.local
+    }
+
+    /// Returns the bounds in world space, projected on the XY plane.
+    ///
+    /// These are the bounds of the transformed local bounds,
+    /// so they may be larger than the bounds of the transformed shape.
+    #[must_use]
+    pub fn world(&self) -> Rect {
+        self.world
+    }
+}
+
+/// Updates the [`ShapeBounds`] of changed shapes.
+///
+/// Must run after the shape meshes are built.
+#[allow(clippy::type_complexity)]
+pub(crate) fn shape_bounds_system(
+    meshes: Res<Assets<Mesh>>,
+    mut query: Query<(Ref<Shape>, &Mesh2d, Ref<GlobalTransform>, &mut ShapeBounds)>,
+) {
+    for (shape, mesh, transform, mut bounds) in &mut query {
+        let local_changed = shape.is_changed() || bounds.is_changed();
+        if !local_changed && !transform.is_changed() {
+            continue;
+        }
+        let local = if local_changed {
+            match bounds.mode {
+                BoundsMode::Exact => meshes.get(&mesh.0).map_or_else(
+                    || exact_path_bounds(&shape),
+                    |mesh| mesh_bounds(mesh).unwrap_or(Rect::EMPTY),
+                ),
+                BoundsMode::Fast => fast_bounds(&shape),
+            }
+        } else {
+            bounds.local
+        };
+        let affine = transform.affine();
+        let world = [
+            local.min,
+            Vec2::new(local.max.x, local.min.y),
+            local.max,
+            Vec2::new(local.min.x, local.max.y),
+        ]
+        .into_iter()
+        .map(|corner| affine.transform_point3(corner.extend(0.0)).truncate())
+        .fold(Rect::EMPTY, |rect, point| rect.union_point(point));
+
+        let new = ShapeBounds {
+            mode: bounds.mode,
+            local,
+            world,
+        };
+        bounds.set_if_neq(new);
+    }
+}
+
+/// Returns the bounds of the vertices of a mesh, if it has any.
+fn mesh_bounds(mesh: &Mesh) -> Option<Rect> {
+    let Some(VertexAttributeValues::Float32x3(positions)) =
+        mesh.attribute(Mesh::ATTRIBUTE_POSITION)
+    else {
+        return None;
+    };
+    if positions.is_empty() {
+        return None;
+    }
+    Some(positions.iter().fold(Rect::EMPTY, |rect, p| {
+        rect.union_point(Vec2::new(p[0], p[1]))
+    }))
+}
+
+/// Returns the exact bounds of the path, enlarged by the stroke.
+///
+/// Used while the mesh is not available.
+fn exact_path_bounds(shape: &Shape) -> Rect {
+    bounding_box(shape.path.iter()).to_rect().inflate(
+        shape
+            .stroke
+            .map_or(0.0, |stroke| stroke_margin(&stroke.options)),
+    )
+}
+
+fn fast_bounds(shape: &Shape) -> Rect {
+    fast_bounding_box(shape.path.iter()).to_rect().inflate(
+        shape
+            .stroke
+            .map_or(0.0, |stroke| stroke_margin(&stroke.options)),
+    )
+}
+
+/// Returns the maximum distance the stroke can reach from the path.
+fn stroke_margin(options: &StrokeOptions) -> f32 {
+    let join = match options.line_join {
+        LineJoin::Miter | LineJoin::MiterClip => options.miter_limit.max(1.0),
+        LineJoin::Round | LineJoin::Bevel => 1.0,
+    };
+    let cap = if options.start_cap == LineCap::Square || options.end_cap == LineCap::Square {
+        std::f32::consts::SQRT_2
+    } else {
+        1.0
+    };
+    options.line_width / 2.0 * join.max(cap)
+}
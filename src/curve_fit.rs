@@ -0,0 +1,458 @@
+//! Fitting of cubic bézier curves to points.
+//!
+//! Points are split at corners,
+//! then each run between corners is approximated by as few smooth curves as
+//! possible, with Schneider's algorithm.
+//! Unlike [`Polygon::simplify`], the result is made of curves.
+
+use std::f32::consts::FRAC_PI_3;
+
+use bevy::math::Vec2;
+
+use crate::{path::ShapePath, shapes::Polygon};
+
+/// Maximum number of reparameterizations of the points before a run is split.
+const MAX_ITERATIONS: usize = 4;
+
+/// Parameters of curve fitting.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CurveFitOptions {
+    /// Maximum distance between the points and the curves.
+    ///
+    /// Default value: `1.0`.
+    pub tolerance: f32,
+    /// Minimum angle, in radians, between consecutive segments for their
+    /// common point to be a corner.
+    ///
+    /// Default value: `PI / 3`.
+    pub corner_angle: f32,
+}
+
+impl Default for CurveFitOptions {
+    fn default() -> Self {
+        Self {
+            tolerance: 1.0,
+            corner_angle: FRAC_PI_3,
+        }
+    }
+}
+
+impl CurveFitOptions {
+    /// Sets the maximum distance between the points and the curves.
+    #[must_use]
+    pub fn with_tolerance(mut self, tolerance: f32) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    /// Sets the minimum angle of corners.
+    #[must_use]
+    pub fn with_corner_angle(mut self, corner_angle: f32) -> Self {
+        self.corner_angle = corner_angle;
+        self
+    }
+}
+
+impl Polygon {
+    /// Returns a path of cubic bézier curves approximating the points,
+    /// such as the samples of a freehand stroke.
+    ///
+    /// The path is empty if there are fewer than two distinct points.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bevy::{color::palettes::css::*, prelude::*};
+    /// use bevy_prototype_lyon::prelude::*;
+    ///
+    /// let samples = shapes::Polygon {
+    ///     points: (0..50)
+    ///         .map(|i| Vec2::new(i as f32 * 4.0, (i as f32 * 0.2).sin() * 30.0))
+    ///         .collect(),
+    ///     closed: false,
+    /// };
+    /// let curves = samples.fit_curves(&CurveFitOptions::default().with_tolerance(0.5));
+    /// let stroke = ShapeBuilder::with(&curves).stroke((BLACK, 3.0)).build();
+    /// ```
+    #[must_use]
+    pub fn fit_curves(&self, options: &CurveFitOptions) -> ShapePath {
+        let mut points = self.points.clone();
+        points.dedup();
+        if self.closed && points.len() > 1 && points.first() == points.last() {
+            points.pop();
+        }
+        if points.len() < 2 {
+            return ShapePath::new();
+        }
+        let n = points.len();
+        let corners = corners(&points, self.closed, options.corner_angle);
+
+        let mut curves = Vec::new();
+        if self.closed {
+            // The loop starts from a corner if there is one,
+            // otherwise the curves join smoothly at the first point.
+            let start = corners.first().copied().unwrap_or(0);
+            points.rotate_left(start);
+            points.push(points[0]);
+            let tangent = if corners.is_empty() {
+                (points[1] - points[n - 1]).normalize_or_zero()
+            } else {
+                Vec2::ZERO
+            };
+            let mut bounds: Vec<usize> = corners.iter().map(|i| (i + n - start) % n).collect();
+            bounds.retain(|i| *i != 0);
+            bounds.insert(0, 0);
+            bounds.push(n);
+            fit_runs(&points, &bounds, tangent, options.tolerance, &mut curves);
+        } else {
+            fit_runs(
+                &points,
+                &corners,
+                Vec2::ZERO,
+                options.tolerance,
+                &mut curves,
+            );
+        }
+
+        let mut path = ShapePath::new().move_to(points[0]);
+        for [_, ctrl1, ctrl2, to] in curves {
+            path = path.cubic_bezier_to(ctrl1, ctrl2, to);
+        }
+        if self.closed {
+            path = path.close();
+        }
+        path
+    }
+}
+
+/// Returns the indices of the corners of `points`,
+/// including the ends of polylines.
+fn corners(points: &[Vec2], closed: bool, corner_angle: f32) -> Vec<usize> {
+    let n = points.len();
+    (0..n)
+        .filter(|i| {
+            let i = *i;
+            let (previous, next) = if closed {
+                (points[(i + n - 1) % n], points[(i + 1) % n])
+            } else if i == 0 || i == n - 1 {
+                return true;
+            } else {
+                (points[i - 1], points[i + 1])
+            };
+            (points[i] - previous).angle_to(next - points[i]).abs() >= corner_angle
+        })
+        .collect()
+}
+
+/// Fits the runs of `points` between consecutive indices of `bounds`.
+///
+/// The ends of the whole sequence have the given `tangent`,
+/// or the direction of their segment if it is zero.
+fn fit_runs(
+    points: &[Vec2],
+    bounds: &[usize],
+    tangent: Vec2,
+    tolerance: f32,
+    curves: &mut Vec<[Vec2; 4]>,
+) {
+    let last = bounds.len().saturating_sub(2);
+    for (i, run) in bounds.windows(2).enumerate() {
+        let run = &points[run[0]..=run[1]];
+        let mut left = (run[1] - run[0]).normalize_or_zero();
+        let mut right = (run[run.len() - 2] - run[run.len() - 1]).normalize_or_zero();
+        if tangent != Vec2::ZERO {
+            if i == 0 {
+                left = tangent;
+            }
+            if i == last {
+                right = -tangent;
+            }
+        }
+        fit_cubic(run, left, right, tolerance * tolerance, curves);
+    }
+}
+
+/// Fits curves to `points`, leaving the ends along the tangents `left` and
+/// `right`, within the squared distance `error`.
+fn fit_cubic(points: &[Vec2], left: Vec2, right: Vec2, error: f32, curves: &mut Vec<[Vec2; 4]>) {
+    let (first, last) = (points[0], points[points.len() - 1]);
+    if points.len() == 2 {
+        let third = first.distance(last) / 3.0;
+        curves.push([first, first + left * third, last + right * third, last]);
+        return;
+    }
+
+    let mut parameters = chord_lengths(points);
+    let mut curve = generate_bezier(points, &parameters, left, right);
+    let (mut max_error, mut split) = fit_error(points, &parameters, &curve);
+    if max_error < error {
+        curves.push(curve);
+        return;
+    }
+    // Close fits are improved by reparameterization before splitting.
+    if max_error < error * 4.0 {
+        for _ in 0..MAX_ITERATIONS {
+            for (point, t) in points.iter().zip(&mut parameters) {
+                *t = newton_raphson(&curve, *point, *t);
+            }
+            curve = generate_bezier(points, &parameters, left, right);
+            (max_error, split) = fit_error(points, &parameters, &curve);
+            if max_error < error {
+                curves.push(curve);
+                return;
+            }
+        }
+    }
+
+    let center = (points[split - 1] - points[split + 1]).normalize_or_zero();
+    fit_cubic(&points[..=split], left, center, error, curves);
+    fit_cubic(&points[split..], -center, right, error, curves);
+}
+
+/// Returns the parameters of `points` proportional to the distance along them.
+fn chord_lengths(points: &[Vec2]) -> Vec<f32> {
+    let mut parameters = Vec::with_capacity(points.len());
+    let mut length = 0.0;
+    parameters.push(0.0);
+    for pair in points.windows(2) {
+        length += pair[0].distance(pair[1]);
+        parameters.push(length);
+    }
+    if length > 0.0 {
+        for t in &mut parameters {
+            *t /= length;
+        }
+    }
+    parameters
+}
+
+/// Returns the curve with the given ends and end tangents that fits
+/// `points` at `parameters` best, in the least squares sense.
+fn generate_bezier(points: &[Vec2], parameters: &[f32], left: Vec2, right: Vec2) -> [Vec2; 4] {
+    let (first, last) = (points[0], points[points.len() - 1]);
+    let mut c = [[0.0; 2]; 2];
+    let mut x = [0.0; 2];
+    for (point, t) in points.iter().zip(parameters) {
+        let [b0, b1, b2, b3] = bernstein(*t);
+        let a = [left * b1, right * b2];
+        c[0][0] += a[0].dot(a[0]);
+        c[0][1] += a[0].dot(a[1]);
+        c[1][1] += a[1].dot(a[1]);
+        let rest = *point - first * (b0 + b1) - last * (b2 + b3);
+        x[0] += a[0].dot(rest);
+        x[1] += a[1].dot(rest);
+    }
+    c[1][0] = c[0][1];
+
+    let det = c[0][0].mul_add(c[1][1], -c[1][0] * c[0][1]);
+    let (alpha_left, alpha_right) = if det == 0.0 {
+        (0.0, 0.0)
+    } else {
+        (
+            x[0].mul_add(c[1][1], -x[1] * c[0][1]) / det,
+            c[0][0].mul_add(x[1], -c[1][0] * x[0]) / det,
+        )
+    };
+
+    // Degenerate or backwards handles fall back to a third of the chord.
+    let length = first.distance(last);
+    let epsilon = 1e-6 * length;
+    let (alpha_left, alpha_right) = if alpha_left < epsilon || alpha_right < epsilon {
+        (length / 3.0, length / 3.0)
+    } else {
+        (alpha_left, alpha_right)
+    };
+    [
+        first,
+        first + left * alpha_left,
+        last + right * alpha_right,
+        last,
+    ]
+}
+
+/// Returns the maximum squared distance between `points` and `curve`
+/// at `parameters`, and the index of the farthest point,
+/// excluding the ends.
+fn fit_error(points: &[Vec2], parameters: &[f32], curve: &[Vec2; 4]) -> (f32, usize) {
+    (1..points.len() - 1)
+        .map(|i| (point(curve, parameters[i]).distance_squared(points[i]), i))
+        .max_by(|(a, _), (b, _)| a.total_cmp(b))
+        .unwrap_or((0.0, points.len() / 2))
+}
+
+/// Improves the parameter `t` of the point of `curve` closest to `target`,
+/// with a step of Newton's method.
+fn newton_raphson(curve: &[Vec2; 4], target: Vec2, t: f32) -> f32 {
+    let difference = point(curve, t) - target;
+    let first = derivative(curve, t);
+    let second = ((curve[2] - curve[1] * 2.0 + curve[0]) * (1.0 - t)
+        + (curve[3] - curve[2] * 2.0 + curve[1]) * t)
+        * 6.0;
+    let denominator = first.dot(first) + difference.dot(second);
+    if denominator == 0.0 {
+        t
+    } else {
+        t - difference.dot(first) / denominator
+    }
+}
+
+/// Returns the Bernstein polynomials of degree three at `t`.
+fn bernstein(t: f32) -> [f32; 4] {
+    let s = 1.0 - t;
+    [s * s * s, 3.0 * s * s * t, 3.0 * s * t * t, t * t * t]
+}
+
+/// Returns the point of `curve` at `t`.
+fn point(curve: &[Vec2; 4], t: f32) -> Vec2 {
+    let [b0, b1, b2, b3] = bernstein(t);
+    curve[0] * b0 + curve[1] * b1 + curve[2] * b2 + curve[3] * b3
+}
+
+/// Returns the derivative of `curve` at `t`.
+fn derivative(curve: &[Vec2; 4], t: f32) -> Vec2 {
+    let s = 1.0 - t;
+    let weights = [s * s, 2.0 * s * t, t * t];
+    ((curve[1] - curve[0]) * weights[0]
+        + (curve[2] - curve[1]) * weights[1]
+        + (curve[3] - curve[2]) * weights[2])
+        * 3.0
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::TAU;
+
+    use lyon_tessellation::path::PathEvent;
+
+    use super::*;
+
+    fn polygon(points: Vec<Vec2>, closed: bool) -> Polygon {
+        Polygon { points, closed }
+    }
+
+    /// Returns the largest distance from `points` to the flattened `path`.
+    fn max_distance(points: &[Vec2], path: &ShapePath) -> f32 {
+        let flattened = path.polygons(0.001);
+        let segments: Vec<(Vec2, Vec2)> = flattened
+            .iter()
+            .flat_map(|polygon| {
+                let n = polygon.points.len();
+                let count = if polygon.closed { n } else { n - 1 };
+                (0..count).map(move |i| (polygon.points[i], polygon.points[(i + 1) % n]))
+            })
+            .collect();
+        points
+            .iter()
+            .map(|point| {
+                segments
+                    .iter()
+                    .map(|(from, to)| {
+                        let vector = *to - *from;
+                        let t = ((*point - *from).dot(vector) / vector.length_squared())
+                            .clamp(0.0, 1.0);
+                        point.distance(*from + vector * t)
+                    })
+                    .fold(f32::INFINITY, f32::min)
+            })
+            .fold(0.0, f32::max)
+    }
+
+    /// Returns the number of curves of `path`.
+    fn curve_count(path: &ShapePath) -> usize {
+        path.build()
+            .iter()
+            .filter(|event| matches!(event, PathEvent::Cubic { .. }))
+            .count()
+    }
+
+    #[test]
+    fn fewer_than_two_points_give_an_empty_path() {
+        let options = CurveFitOptions::default();
+        for closed in [false, true] {
+            for points in [vec![], vec![Vec2::ONE], vec![Vec2::ONE, Vec2::ONE]] {
+                let path = polygon(points, closed).fit_curves(&options);
+                assert_eq!(path.build().iter().count(), 0);
+            }
+        }
+    }
+
+    #[test]
+    fn two_points_give_a_straight_curve() {
+        let points = vec![Vec2::ZERO, Vec2::new(10.0, 0.0)];
+        let path = polygon(points, false).fit_curves(&CurveFitOptions::default());
+        assert_eq!(curve_count(&path), 1);
+        assert!(max_distance(&[Vec2::new(5.0, 0.0)], &path) < 1e-3);
+    }
+
+    #[test]
+    fn open_curves_stay_within_tolerance() {
+        let points: Vec<Vec2> = (0..100)
+            .map(|i| {
+                let x = i as f32 * 2.0;
+                Vec2::new(x, (x * 0.05).sin() * 40.0)
+            })
+            .collect();
+        let samples = polygon(points.clone(), false);
+        for tolerance in [0.1, 1.0, 5.0] {
+            let path = samples.fit_curves(&CurveFitOptions::default().with_tolerance(tolerance));
+            let distance = max_distance(&points, &path);
+            assert!(distance <= tolerance, "{distance} > {tolerance}");
+            assert!(curve_count(&path) < points.len() / 4);
+        }
+    }
+
+    #[test]
+    fn closed_curves_stay_within_tolerance() {
+        let points: Vec<Vec2> = (0..64)
+            .map(|i| Vec2::from_angle(i as f32 * TAU / 64.0) * 50.0)
+            .collect();
+        let options = CurveFitOptions::default().with_tolerance(0.5);
+        let path = polygon(points.clone(), true).fit_curves(&options);
+        assert!(max_distance(&points, &path) <= 0.5);
+        assert!(path
+            .build()
+            .iter()
+            .any(|event| matches!(event, PathEvent::End { close: true, .. })));
+
+        // A repeated first point is ignored.
+        let mut repeated = points.clone();
+        repeated.push(points[0]);
+        let path = polygon(repeated, true).fit_curves(&options);
+        assert!(max_distance(&points, &path) <= 0.5);
+    }
+
+    #[test]
+    fn corners_are_kept() {
+        // A square sampled along its edges.
+        let points: Vec<Vec2> = (0..40)
+            .map(|i| {
+                let t = (i % 10) as f32;
+                match i / 10 {
+                    0 => Vec2::new(t, 0.0),
+                    1 => Vec2::new(10.0, t),
+                    2 => Vec2::new(10.0 - t, 10.0),
+                    _ => Vec2::new(0.0, 10.0 - t),
+                }
+            })
+            .collect();
+        let path = polygon(points.clone(), true).fit_curves(&CurveFitOptions::default());
+        assert_eq!(curve_count(&path), 4);
+        assert!(max_distance(&points, &path) <= 1.0);
+        let ends: Vec<Vec2> = path
+            .build()
+            .iter()
+            .filter_map(|event| match event {
+                PathEvent::Cubic { to, .. } => Some(Vec2::new(to.x, to.y)),
+                _ => None,
+            })
+            .collect();
+        for corner in [
+            Vec2::ZERO,
+            Vec2::new(10.0, 0.0),
+            Vec2::splat(10.0),
+            Vec2::new(0.0, 10.0),
+        ] {
+            assert!(ends.contains(&corner), "{corner} is not kept");
+        }
+    }
+}
@@ -0,0 +1,413 @@
+//! Closest point and distance queries on paths.
+//!
+//! Queries are available on [`Shape`] and on [`ShapePath`],
+//! in the local space of the geometry.
+//! Lines and quadratic bézier curves are solved analytically,
+//! as well as the circular arcs of a [`ShapePath`].
+//! Cubic bézier curves and elliptical arcs are solved numerically,
+//! to a precision far below the tessellation tolerance.
+
+use bevy::math::Vec2;
+use lyon_algorithms::hit_test::hit_test_path;
+use lyon_tessellation::{
+    geom::{Arc, CubicBezierSegment, LineSegment, QuadraticBezierSegment},
+    math::Point,
+    path::{Path, PathEvent},
+    FillOptions, FillRule,
+};
+
+use crate::{entity::Shape, path::ShapePath, utils::ToPoint};
+
+/// Tolerance used to measure the length of cubic bézier curves and arcs.
+const LENGTH_TOLERANCE: f32 = 0.001;
+
+/// Number of samples used to bracket the closest point on curves
+/// that are solved numerically.
+const SAMPLES: usize = 16;
+
+/// Number of iterations refining the closest point inside its bracket.
+const REFINE_ITERATIONS: usize = 40;
+
+/// Location of a point on a path.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PathLocation {
+    /// Position of the point.
+    pub point: Vec2,
+    /// Distance from the queried point.
+    pub distance: f32,
+    /// Index of the segment containing the point.
+    ///
+    /// Segments are numbered in drawing order across all subpaths,
+    /// including the lines closing the subpaths.
+    pub segment: usize,
+    /// Parameter of the point on its segment, between `0.0` and `1.0`.
+    pub t: f32,
+    /// Length of the path from its beginning to the point.
+    pub arc_length: f32,
+}
+
+impl Shape {
+    /// Returns the point of the path nearest to `point`,
+    /// or `None` if the path has no segments.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bevy::{color::palettes::css::*, prelude::*};
+    /// use bevy_prototype_lyon::prelude::*;
+    ///
+    /// let rect = shapes::Rectangle {
+    ///     extents: Vec2::splat(20.0),
+    ///     ..default()
+    /// };
+    /// let shape = ShapeBuilder::with(&rect).fill(RED).build();
+    ///
+    /// let location = shape.closest_point(Vec2::new(15.0, 0.0)).unwrap();
+    /// assert_eq!(location.point, Vec2::new(10.0, 0.0));
+    /// assert_eq!(location.distance, 5.0);
+    /// ```
+    #[must_use]
+    pub fn closest_point(&self, point: Vec2) -> Option<PathLocation> {
+        closest_point(&path_segments(&self.path), point)
+    }
+
+    /// Returns the distance from `point` to the path,
+    /// negative if `point` is inside the fill, according to the fill rule,
+    /// or `None` if the path has no segments.
+    ///
+    /// Shapes without fill use the default fill rule of [`FillOptions`].
+    #[must_use]
+    pub fn signed_distance(&self, point: Vec2) -> Option<f32> {
+        let options = self.fill.map_or(FillOptions::DEFAULT, |fill| fill.options);
+        signed_distance(&self.path, &path_segments(&self.path), point, options)
+    }
+}
+
+impl ShapePath {
+    /// Returns the point of the path nearest to `point`,
+    /// or `None` if the path has no segments.
+    ///
+    /// Unlike [`Shape::closest_point`], arcs are not approximated by
+    /// bézier curves, and count as a single segment.
+    #[must_use]
+    pub fn closest_point(&self, point: Vec2) -> Option<PathLocation> {
+        closest_point(&self.segments(), point)
+    }
+
+    /// Returns the distance from `point` to the path,
+    /// negative if `point` is inside the path, according to `fill_rule`,
+    /// or `None` if the path has no segments.
+    #[must_use]
+    pub fn signed_distance(&self, point: Vec2, fill_rule: FillRule) -> Option<f32> {
+        let path = self.build();
+        let options = FillOptions::DEFAULT.with_fill_rule(fill_rule);
+        signed_distance(&path, &self.segments(), point, options)
+    }
+}
+
+/// A segment of a path.
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum Segment {
+    Line(LineSegment<f32>),
+    Quadratic(QuadraticBezierSegment<f32>),
+    Cubic(CubicBezierSegment<f32>),
+    Arc(Arc<f32>),
+}
+
+impl Segment {
+    fn sample(&self, t: f32) -> Point {
+        match self {
+            Self::Line(line) => line.sample(t),
+            Self::Quadratic(quadratic) => quadratic.sample(t),
+            Self::Cubic(cubic) => cubic.sample(t),
+            Self::Arc(arc) => arc.sample(t),
+        }
+    }
+
+    /// Returns the parameter of the point of the segment nearest to `point`.
+    fn closest_t(&self, point: Point) -> f32 {
+        match self {
+            Self::Line(line) => {
+                let vector = line.to_vector();
+                let length = vector.square_length();
+                if length > 0.0 {
+                    ((point - line.from).dot(vector) / length).clamp(0.0, 1.0)
+                } else {
+                    0.0
+                }
+            }
+            Self::Quadratic(quadratic) => quadratic.closest_point(point),
+            Self::Arc(arc) if (arc.radii.x - arc.radii.y).abs() <= f32::EPSILON * arc.radii.x => {
+                closest_t_circular(arc, point)
+            }
+            Self::Cubic(_) | Self::Arc(_) => self.refine_closest_t(point),
+        }
+    }
+
+    /// Finds the closest point by sampling the segment,
+    /// then refining the best sample with a ternary search.
+    fn refine_closest_t(&self, point: Point) -> f32 {
+        let distance = |t: f32| (self.sample(t) - point).square_length();
+        let step = 1.0 / SAMPLES as f32;
+        let best = (0..=SAMPLES)
+            .map(|i| i as f32 * step)
+            .min_by(|a, b| distance(*a).total_cmp(&distance(*b)))
+            .unwrap_or_default();
+        let (mut low, mut high) = ((best - step).max(0.0), (best + step).min(1.0));
+        for _ in 0..REFINE_ITERATIONS {
+            let a = low + (high - low) / 3.0;
+            let b = high - (high - low) / 3.0;
+            if distance(a) < distance(b) {
+                high = b;
+            } else {
+                low = a;
+            }
+        }
+        #[allow(clippy::manual_midpoint)] // `f32::midpoint` needs Rust 1.85.
+        let t = (low + high) / 2.0;
+        [t, 0.0, 1.0]
+            .into_iter()
+            .min_by(|a, b| distance(*a).total_cmp(&distance(*b)))
+            .unwrap_or(t)
+    }
+
+    /// Returns the length of the segment.
+    pub(crate) fn length(&self) -> f32 {
+        match self {
+            Self::Line(line) => line.length(),
+            Self::Quadratic(quadratic) => quadratic.length(),
+            Self::Cubic(cubic) => cubic.approximate_length(LENGTH_TOLERANCE),
+            Self::Arc(arc) => arc.approximate_length(LENGTH_TOLERANCE),
+        }
+    }
+
+    /// Returns the length of the segment from its start to `t`.
+    pub(crate) fn length_to(&self, t: f32) -> f32 {
+        match self {
+            Self::Line(line) => line.length() * t,
+            Self::Quadratic(quadratic) => quadratic.split_range(0.0..t).length(),
+            Self::Cubic(cubic) => cubic
+                .split_range(0.0..t)
+                .approximate_length(LENGTH_TOLERANCE),
+            Self::Arc(arc) => arc.split_range(0.0..t).approximate_length(LENGTH_TOLERANCE),
+        }
+    }
+}
+
+/// Returns the parameter of the point of a circular arc nearest to `point`.
+fn closest_t_circular(arc: &Arc<f32>, point: Point) -> f32 {
+    let sweep = arc.sweep_angle.radians;
+    if sweep == 0.0 {
+        return 0.0;
+    }
+    let angle = (point - arc.center).angle_from_x_axis().radians
+        - arc.x_rotation.radians
+        - arc.start_angle.radians;
+    // Angle from the start of the arc, in the direction of the sweep.
+    let along = (angle * sweep.signum()).rem_euclid(std::f32::consts::TAU);
+    if along <= sweep.abs() {
+        return along / sweep.abs();
+    }
+    let to_start = (arc.from() - point).square_length();
+    let to_end = (arc.to() - point).square_length();
+    if to_start <= to_end {
+        0.0
+    } else {
+        1.0
+    }
+}
+
+/// Returns the segments of a lyon path.
+pub(crate) fn path_segments(path: &Path) -> Vec<Segment> {
+    path.iter()
+        .filter_map(|event| match event {
+            PathEvent::Begin { .. } => None,
+            PathEvent::Line { from, to } => Some(Segment::Line(LineSegment { from, to })),
+            PathEvent::Quadratic { from, ctrl, to } => {
+                Some(Segment::Quadratic(QuadraticBezierSegment {
+                    from,
+                    ctrl,
+                    to,
+                }))
+            }
+            PathEvent::Cubic {
+                from,
+                ctrl1,
+                ctrl2,
+                to,
+            } => Some(Segment::Cubic(CubicBezierSegment {
+                from,
+                ctrl1,
+                ctrl2,
+                to,
+            })),
+            PathEvent::End { last, first, close } => {
+                (close && last != first).then_some(Segment::Line(LineSegment {
+                    from: last,
+                    to: first,
+                }))
+            }
+        })
+        .collect()
+}
+
+fn closest_point(segments: &[Segment], point: Vec2) -> Option<PathLocation> {
+    let target = point.to_point();
+    let (segment, t, closest) = segments
+        .iter()
+        .enumerate()
+        .map(|(i, segment)| {
+            let t = segment.closest_t(target);
+            (i, t, segment.sample(t))
+        })
+        .min_by(|(_, _, a), (_, _, b)| {
+            (*a - target)
+                .square_length()
+                .total_cmp(&(*b - target).square_length())
+        })?;
+    let arc_length = segments[..segment].iter().map(Segment::length).sum::<f32>()
+        + segments[segment].length_to(t);
+    Some(PathLocation {
+        point: Vec2::new(closest.x, closest.y),
+        distance: (closest - target).length(),
+        segment,
+        t,
+        arc_length,
+    })
+}
+
+fn signed_distance(
+    path: &Path,
+    segments: &[Segment],
+    point: Vec2,
+    options: FillOptions,
+) -> Option<f32> {
+    let distance = closest_point(segments, point)?.distance;
+    let inside = hit_test_path(
+        &point.to_point(),
+        path.iter(),
+        options.fill_rule,
+        options.tolerance,
+    );
+    Some(if inside { -distance } else { distance })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::{FRAC_PI_2, FRAC_PI_4, PI, SQRT_2};
+
+    use super::*;
+
+    const EPSILON: f32 = 1e-3;
+
+    /// Where the path is nearly perpendicular to the query,
+    /// the closest point is less precise than its distance.
+    fn assert_near(a: Vec2, b: Vec2) {
+        assert!(a.distance(b) < 1e-2, "{a} != {b}");
+    }
+
+    #[test]
+    fn closest_point_on_cubic() {
+        let path = ShapePath::new().move_to(Vec2::ZERO).cubic_bezier_to(
+            Vec2::new(0.0, 10.0),
+            Vec2::new(10.0, 10.0),
+            Vec2::new(10.0, 0.0),
+        );
+
+        let location = path.closest_point(Vec2::new(5.0, 20.0)).unwrap();
+        assert_near(location.point, Vec2::new(5.0, 7.5));
+        assert!((location.distance - 12.5).abs() < EPSILON);
+        assert!((location.t - 0.5).abs() < EPSILON);
+        let half_length = path.segments()[0].length() / 2.0;
+        assert!((location.arc_length - half_length).abs() < 1e-2);
+
+        // Beyond the end of the curve, the end point is the closest.
+        let location = path.closest_point(Vec2::new(20.0, -5.0)).unwrap();
+        assert_near(location.point, Vec2::new(10.0, 0.0));
+        assert!((location.t - 1.0).abs() < EPSILON);
+    }
+
+    #[test]
+    fn closest_point_on_circular_arc() {
+        let path = ShapePath::new().move_to(Vec2::new(10.0, 0.0)).arc(
+            Vec2::ZERO,
+            Vec2::splat(10.0),
+            FRAC_PI_2,
+            0.0,
+        );
+
+        let location = path.closest_point(Vec2::splat(20.0)).unwrap();
+        assert_near(location.point, Vec2::splat(5.0 * SQRT_2));
+        let expected = Vec2::splat(20.0).length() - 10.0;
+        assert!((location.distance - expected).abs() < EPSILON);
+        assert!((location.t - 0.5).abs() < EPSILON);
+        assert!((location.arc_length / 10.0 - FRAC_PI_4).abs() < 1e-3);
+
+        // Outside of the sweep, the nearest end point is the closest.
+        let location = path.closest_point(Vec2::new(-5.0, 20.0)).unwrap();
+        assert_near(location.point, Vec2::new(0.0, 10.0));
+    }
+
+    #[test]
+    fn closest_point_on_elliptical_arc() {
+        let path = ShapePath::new().move_to(Vec2::new(20.0, 0.0)).arc(
+            Vec2::ZERO,
+            Vec2::new(20.0, 10.0),
+            PI,
+            0.0,
+        );
+
+        let location = path.closest_point(Vec2::new(0.0, 30.0)).unwrap();
+        assert_near(location.point, Vec2::new(0.0, 10.0));
+        assert!((location.distance - 20.0).abs() < EPSILON);
+        assert!((location.t - 0.5).abs() < EPSILON);
+    }
+
+    #[test]
+    fn signed_distance_of_closed_path() {
+        let square = ShapePath::new()
+            .move_to(Vec2::new(-10.0, -10.0))
+            .line_to(Vec2::new(10.0, -10.0))
+            .line_to(Vec2::new(10.0, 10.0))
+            .line_to(Vec2::new(-10.0, 10.0))
+            .close();
+
+        let inside = square.signed_distance(Vec2::ZERO, FillRule::NonZero);
+        assert!(inside.is_some_and(|d| (d + 10.0).abs() < EPSILON));
+        let outside = square.signed_distance(Vec2::new(15.0, 0.0), FillRule::NonZero);
+        assert!(outside.is_some_and(|d| (d - 5.0).abs() < EPSILON));
+        // The line closing the path is part of its outline.
+        let location = square.closest_point(Vec2::new(-12.0, 0.0)).unwrap();
+        assert_eq!(location.segment, 3);
+        assert!((location.distance - 2.0).abs() < EPSILON);
+    }
+
+    #[test]
+    fn signed_distance_follows_fill_rule() {
+        // Two nested squares with the same winding.
+        let path = ShapePath::new()
+            .move_to(Vec2::new(-10.0, -10.0))
+            .line_to(Vec2::new(10.0, -10.0))
+            .line_to(Vec2::new(10.0, 10.0))
+            .line_to(Vec2::new(-10.0, 10.0))
+            .close()
+            .move_to(Vec2::new(-5.0, -5.0))
+            .line_to(Vec2::new(5.0, -5.0))
+            .line_to(Vec2::new(5.0, 5.0))
+            .line_to(Vec2::new(-5.0, 5.0))
+            .close();
+
+        let non_zero = path.signed_distance(Vec2::ZERO, FillRule::NonZero);
+        assert!(non_zero.is_some_and(|d| (d + 5.0).abs() < EPSILON));
+        let even_odd = path.signed_distance(Vec2::ZERO, FillRule::EvenOdd);
+        assert!(even_odd.is_some_and(|d| (d - 5.0).abs() < EPSILON));
+    }
+
+    #[test]
+    fn empty_path_has_no_distance() {
+        assert!(ShapePath::new().closest_point(Vec2::ZERO).is_none());
+        assert!(ShapePath::new()
+            .signed_distance(Vec2::ZERO, FillRule::NonZero)
+            .is_none());
+    }
+}
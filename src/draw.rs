@@ -1,15 +1,31 @@
 //! Types for defining shape color and options.
 
-use bevy::color::{Color, Hsla, Hsva, Hwba, Laba, Lcha, LinearRgba, Oklaba, Oklcha, Srgba, Xyza};
-use lyon_tessellation::{FillOptions, StrokeOptions};
+use std::borrow::Cow;
+
+use bevy::{
+    color::{Color, Hsla, Hsva, Hwba, Laba, Lcha, LinearRgba, Oklaba, Oklcha, Srgba, Xyza},
+    math::{Affine2, Vec2},
+};
+use lyon_tessellation::{
+    path::{Path, PathEvent},
+    FillOptions, StrokeOptions,
+};
+
+use crate::{gradient::Gradient, measure::PathMeasure};
 
 /// Defines the fill options for the lyon tessellator and color of the generated
 /// vertices.
+///
+/// If `gradient` is set, the fill is painted with it instead of `color`
+/// (see [`GradientMaterial`](crate::gradient::GradientMaterial)).
+/// The texture coordinates of the generated vertices are defined by `uv`.
 #[allow(missing_docs)]
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Fill {
     pub options: FillOptions,
     pub color: Color,
+    pub gradient: Option<Gradient>,
+    pub uv: UvMapping,
 }
 
 impl Fill {
@@ -19,8 +35,49 @@ impl Fill {
         Self {
             options: FillOptions::default(),
             color: color.into(),
+            gradient: None,
+            uv: UvMapping::default(),
+        }
+    }
+
+    /// Convenience constructor requiring only the [`Gradient`].
+    #[must_use]
+    pub fn gradient(gradient: Gradient) -> Self {
+        Self {
+            options: FillOptions::default(),
+            color: gradient.first_color(),
+            gradient: Some(gradient),
+            uv: UvMapping::default(),
         }
     }
+
+    /// Sets the color of the fill.
+    #[must_use]
+    pub fn with_color(mut self, color: impl Into<Color>) -> Self {
+        self.color = color.into();
+        self
+    }
+
+    /// Sets the [`Gradient`] of the fill.
+    #[must_use]
+    pub fn with_gradient(mut self, gradient: Gradient) -> Self {
+        self.gradient = Some(gradient);
+        self
+    }
+
+    /// Sets the tessellation options of the fill.
+    #[must_use]
+    pub fn with_options(mut self, options: FillOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Sets the [`UvMapping`] of the fill.
+    #[must_use]
+    pub fn with_uv(mut self, uv: UvMapping) -> Self {
+        self.uv = uv;
+        self
+    }
 }
 
 impl Default for Fill {
@@ -28,10 +85,18 @@ impl Default for Fill {
         Self {
             options: FillOptions::default(),
             color: bevy::color::palettes::css::WHITE.into(),
+            gradient: None,
+            uv: UvMapping::default(),
         }
     }
 }
 
+impl From<Gradient> for Fill {
+    fn from(gradient: Gradient) -> Self {
+        Self::gradient(gradient)
+    }
+}
+
 impl From<Color> for Fill {
     fn from(color: Color) -> Self {
         Self::color(color)
@@ -98,13 +163,70 @@ impl From<Xyza> for Fill {
     }
 }
 
+/// Defines how the texture coordinates (`Mesh::ATTRIBUTE_UV_0`) of a [`Fill`]
+/// are generated from the local positions of its vertices.
+///
+/// The `v` axis points down, as in image space.
+/// Mappings producing coordinates outside of the `0.0..=1.0` range
+/// need a texture sampler using a repeating address mode.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum UvMapping {
+    /// The texture is stretched to fit the bounding box of the path.
+    #[default]
+    BoundingBox,
+    /// The texture is repeated every `size` units, starting at the origin.
+    ///
+    /// Both components of `size` must be positive.
+    /// Smaller values are clamped to `f32::EPSILON`,
+    /// and panic in debug builds.
+    Tiled(Vec2),
+    /// The texture coordinates are computed with a custom transform
+    /// from local positions.
+    Affine(Affine2),
+}
+
+impl UvMapping {
+    /// Returns the transform from local positions to texture coordinates.
+    pub(crate) fn to_affine(self, path: &Path) -> Affine2 {
+        match self {
+            Self::BoundingBox => {
+                let bounds = lyon_algorithms::aabb::bounding_box(path.iter());
+                let size =
+                    Vec2::new(bounds.width(), bounds.height()).max(Vec2::splat(f32::EPSILON));
+                Affine2::from_scale_angle_translation(
+                    Vec2::new(1.0 / size.x, -1.0 / size.y),
+                    0.0,
+                    Vec2::new(-bounds.min.x / size.x, bounds.max.y / size.y),
+                )
+            }
+            Self::Tiled(size) => {
+                debug_assert!(
+                    size.cmpgt(Vec2::ZERO).all(),
+                    "the tile size of `UvMapping::Tiled` must be positive, got {size}"
+                );
+                let size = size.max(Vec2::splat(f32::EPSILON));
+                Affine2::from_scale(Vec2::new(1.0 / size.x, -1.0 / size.y))
+            }
+            Self::Affine(affine) => affine,
+        }
+    }
+}
+
 /// Defines the stroke options for the lyon tessellator and color of the
 /// generated vertices.
+///
+/// If `gradient` is set, the stroke is painted with it instead of `color`
+/// (see [`GradientMaterial`](crate::gradient::GradientMaterial)).
+/// The texture coordinates of the generated vertices are defined by `uv`.
+/// Only the portion of the path selected by `trim` is stroked.
 #[allow(missing_docs)]
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Stroke {
     pub options: StrokeOptions,
     pub color: Color,
+    pub gradient: Option<Gradient>,
+    pub uv: StrokeUvMapping,
+    pub trim: Trim,
 }
 
 impl Stroke {
@@ -114,6 +236,9 @@ impl Stroke {
         Self {
             options: StrokeOptions::default().with_line_width(line_width),
             color: color.into(),
+            gradient: None,
+            uv: StrokeUvMapping::default(),
+            trim: Trim::FULL,
         }
     }
 
@@ -123,6 +248,72 @@ impl Stroke {
         Self {
             options: StrokeOptions::default(),
             color: color.into(),
+            gradient: None,
+            uv: StrokeUvMapping::default(),
+            trim: Trim::FULL,
+        }
+    }
+
+    /// Constructor that requires a [`Gradient`] and a line width.
+    #[must_use]
+    pub fn gradient(gradient: Gradient, line_width: f32) -> Self {
+        Self {
+            options: StrokeOptions::default().with_line_width(line_width),
+            color: gradient.first_color(),
+            gradient: Some(gradient),
+            uv: StrokeUvMapping::default(),
+            trim: Trim::FULL,
+        }
+    }
+
+    /// Sets the color of the stroke.
+    #[must_use]
+    pub fn with_color(mut self, color: impl Into<Color>) -> Self {
+        self.color = color.into();
+        self
+    }
+
+    /// Sets the [`Gradient`] of the stroke.
+    #[must_use]
+    pub fn with_gradient(mut self, gradient: Gradient) -> Self {
+        self.gradient = Some(gradient);
+        self
+    }
+
+    /// Sets the tessellation options of the stroke.
+    #[must_use]
+    pub fn with_options(mut self, options: StrokeOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Sets the line width of the stroke.
+    #[must_use]
+    pub fn with_line_width(mut self, line_width: f32) -> Self {
+        self.options.line_width = line_width;
+        self
+    }
+
+    /// Sets the [`StrokeUvMapping`] of the stroke.
+    #[must_use]
+    pub fn with_uv(mut self, uv: StrokeUvMapping) -> Self {
+        self.uv = uv;
+        self
+    }
+
+    /// Sets the [`Trim`] of the stroke.
+    #[must_use]
+    pub fn with_trim(mut self, trim: Trim) -> Self {
+        self.trim = trim;
+        self
+    }
+
+    /// Returns the portion of `path` drawn by the stroke.
+    pub(crate) fn path<'a>(&self, path: &'a Path) -> Cow<'a, Path> {
+        if self.trim.is_full() {
+            Cow::Borrowed(path)
+        } else {
+            Cow::Owned(self.trim.apply(path, self.options.tolerance))
         }
     }
 }
@@ -132,10 +323,163 @@ impl Default for Stroke {
         Self {
             options: StrokeOptions::default(),
             color: bevy::color::palettes::css::BLACK.into(),
+            gradient: None,
+            uv: StrokeUvMapping::default(),
+            trim: Trim::FULL,
         }
     }
 }
 
+impl From<(Gradient, f32)> for Stroke {
+    fn from(value: (Gradient, f32)) -> Self {
+        Self::gradient(value.0, value.1)
+    }
+}
+
+/// Defines how the texture coordinates (`Mesh::ATTRIBUTE_UV_0`) of a
+/// [`Stroke`] are generated.
+///
+/// The `u` coordinate runs along the path,
+/// while `v` goes from `0.0` on the left side of the line
+/// to `1.0` on its right side.
+///
+/// The distance along the path accumulates across subpaths,
+/// in drawing order,
+/// so each subpath continues the texture where the previous one ended,
+/// whether it is closed or not.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum StrokeUvMapping {
+    /// The texture is stretched once along the whole path.
+    #[default]
+    Stretch,
+    /// The texture is repeated every given number of units along the path.
+    ///
+    /// Requires a texture sampler using a repeating address mode.
+    Tiled(f32),
+}
+
+impl StrokeUvMapping {
+    /// Returns the transform from the distance along the path and side of a
+    /// vertex to texture coordinates,
+    /// given the `total` length of the stroked path.
+    pub(crate) fn to_affine(self, total: f32) -> Affine2 {
+        let length = match self {
+            Self::Stretch => total,
+            Self::Tiled(length) => length,
+        };
+        Affine2::from_scale(Vec2::new(1.0 / length.max(f32::EPSILON), 1.0))
+    }
+}
+
+/// Portion of a path drawn by a [`Stroke`],
+/// between two fractions of the length of the path.
+///
+/// The portion is moved along the path by `offset`, also a fraction of the
+/// length, wrapping around the end of the path.
+/// Custom vertex attributes see the endpoints of the trimmed path.
+///
+/// # Example
+///
+/// ```
+/// use bevy::{color::palettes::css::*, prelude::*};
+/// use bevy_prototype_lyon::prelude::*;
+///
+/// // Draws the circle progressively.
+/// fn draw_on_system(time: Res<Time>, mut query: Query<&mut Shape>) {
+///     let progress = (time.elapsed_secs() / 2.0).min(1.0);
+///     for mut shape in &mut query {
+///         if let Some(stroke) = &mut shape.stroke {
+///             stroke.trim = Trim::new(0.0, progress);
+///         }
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Trim {
+    /// Fraction of the length where the drawn portion starts.
+    pub start: f32,
+    /// Fraction of the length where the drawn portion ends.
+    pub end: f32,
+    /// Fraction of the length added to `start` and `end`.
+    pub offset: f32,
+}
+
+impl Trim {
+    /// Trim drawing the whole path.
+    pub const FULL: Self = Self::new(0.0, 1.0);
+
+    /// Creates a trim drawing the path from `start` to `end`.
+    #[must_use]
+    pub const fn new(start: f32, end: f32) -> Self {
+        Self {
+            start,
+            end,
+            offset: 0.0,
+        }
+    }
+
+    /// Sets the offset of the trim.
+    #[must_use]
+    pub const fn with_offset(mut self, offset: f32) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    /// Returns `true` if the whole path is drawn.
+    fn is_full(&self) -> bool {
+        self.end - self.start >= 1.0
+    }
+
+    /// Returns the drawn portion of `path`,
+    /// measured by flattening curves with `tolerance`.
+    fn apply(&self, path: &Path, tolerance: f32) -> Path {
+        let span = self.end - self.start;
+        if span <= 0.0 {
+            return Path::new();
+        }
+        let measure = PathMeasure::new(path, tolerance);
+        let length = measure.length();
+        let start = (self.start + self.offset).rem_euclid(1.0);
+        let end = start + span;
+        let head = measure.slice(start * length..end.min(1.0) * length);
+        if end <= 1.0 {
+            return head;
+        }
+        let tail = measure.slice(0.0..(end - 1.0) * length);
+        let mut builder = Path::builder();
+        if is_single_closed(path) {
+            // The portion continues across the start of the subpath.
+            for event in head
+                .iter()
+                .filter(|event| !matches!(event, PathEvent::End { .. }))
+            {
+                builder.path_event(event);
+            }
+            for event in tail.iter().skip(1) {
+                builder.path_event(event);
+            }
+        } else {
+            builder.extend_from_paths(&[head.as_slice(), tail.as_slice()]);
+        }
+        builder.build()
+    }
+}
+
+impl Default for Trim {
+    fn default() -> Self {
+        Self::FULL
+    }
+}
+
+/// Returns `true` if `path` is made of a single closed subpath.
+fn is_single_closed(path: &Path) -> bool {
+    let mut ends = path.iter().filter_map(|event| match event {
+        PathEvent::End { close, .. } => Some(close),
+        _ => None,
+    });
+    ends.next() == Some(true) && ends.next().is_none()
+}
+
 impl From<(Color, f32)> for Stroke {
     fn from(value: (Color, f32)) -> Self {
         Self::new(value.0, value.1)
@@ -201,3 +545,88 @@ impl From<(Xyza, f32)> for Stroke {
         Self::new(value.0, value.1)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::path::ShapePath;
+
+    const EPSILON: f32 = 1e-3;
+
+    /// A square of side 10, closed or not.
+    fn square(close: bool) -> Path {
+        let path = ShapePath::new()
+            .move_to(Vec2::ZERO)
+            .line_to(Vec2::new(10.0, 0.0))
+            .line_to(Vec2::new(10.0, 10.0))
+            .line_to(Vec2::new(0.0, 10.0));
+        if close {
+            path.close().build()
+        } else {
+            path.line_to(Vec2::ZERO).build()
+        }
+    }
+
+    fn subpaths(path: &Path) -> usize {
+        path.iter()
+            .filter(|event| matches!(event, PathEvent::Begin { .. }))
+            .count()
+    }
+
+    fn length(path: &Path) -> f32 {
+        PathMeasure::new(path, 0.1).length()
+    }
+
+    #[test]
+    fn trim_wraps_around_closed_path() {
+        let trimmed = Trim::new(0.0, 0.5)
+            .with_offset(0.75)
+            .apply(&square(true), 0.1);
+        assert_eq!(subpaths(&trimmed), 1);
+        assert!((length(&trimmed) - 20.0).abs() < EPSILON);
+        // The portion starts three quarters along the square.
+        let Some(PathEvent::Begin { at }) = trimmed.iter().next() else {
+            panic!("the trimmed path should begin with a subpath");
+        };
+        assert!(Vec2::new(at.x, at.y).distance(Vec2::new(0.0, 10.0)) < EPSILON);
+    }
+
+    #[test]
+    fn trim_splits_open_path_at_its_end() {
+        let trimmed = Trim::new(0.0, 0.5)
+            .with_offset(0.75)
+            .apply(&square(false), 0.1);
+        assert_eq!(subpaths(&trimmed), 2);
+        assert!((length(&trimmed) - 20.0).abs() < EPSILON);
+    }
+
+    #[test]
+    fn trim_without_wrapping_is_a_single_slice() {
+        let trimmed = Trim::new(0.25, 0.5).apply(&square(true), 0.1);
+        assert_eq!(subpaths(&trimmed), 1);
+        assert!((length(&trimmed) - 10.0).abs() < EPSILON);
+    }
+
+    #[test]
+    fn empty_trim_draws_nothing() {
+        assert!(Trim::new(0.5, 0.5)
+            .apply(&square(true), 0.1)
+            .iter()
+            .next()
+            .is_none());
+        assert!(Trim::new(0.75, 0.25)
+            .apply(&square(true), 0.1)
+            .iter()
+            .next()
+            .is_none());
+    }
+
+    #[test]
+    fn trim_is_full_from_a_span_of_one() {
+        assert!(Trim::FULL.is_full());
+        assert!(Trim::new(0.25, 1.25).is_full());
+        assert!(Trim::new(0.0, 2.0).is_full());
+        assert!(!Trim::new(0.0, 0.999).is_full());
+        assert!(!Trim::new(0.5, 1.0).with_offset(0.5).is_full());
+    }
+}
@@ -1,14 +1,17 @@
 //! Custom Bevy ECS bundle for shapes.
 #![expect(deprecated)]
 
-use bevy::prelude::*;
-use lyon_algorithms::path::Builder;
+use bevy::{prelude::*, sprite::Anchor};
+use lyon_algorithms::{aabb::bounding_box, path::Builder};
 use lyon_tessellation::{self as tess};
 
 use crate::{
+    bounds::ShapeBounds,
     draw::{Fill, Stroke},
+    fit::FitMode,
     geometry::Geometry,
     plugin::COLOR_MATERIAL_HANDLE,
+    utils::{ToRect, ToTransform},
 };
 
 /// A Bevy `Bundle` to represent a shape.
@@ -38,8 +41,12 @@ impl Default for ShapeBundle {
 /// `Component` describing a geometric shape.
 ///
 /// It can be constructed using `ShapeBuilder`.
+///
+/// Shapes are drawn with a default [`ColorMaterial`],
+/// unless they are spawned with a material registered with
+/// [`ShapeMaterialPlugin`](crate::plugin::ShapeMaterialPlugin).
 #[derive(Component, Default, Clone)]
-#[require(Mesh2d, MeshMaterial2d<ColorMaterial>(color_material_handle), Transform, Visibility)]
+#[require(Mesh2d, ShapeBounds, Transform, Visibility)]
 #[non_exhaustive]
 pub struct Shape {
     /// Geometry of a shape.
@@ -51,9 +58,65 @@ pub struct Shape {
 }
 
 impl Shape {
+    #[allow(clippy::large_types_passed_by_value)] // Gradients make paints large.
     pub(crate) fn new(path: tess::path::Path, fill: Option<Fill>, stroke: Option<Stroke>) -> Self {
         Self { path, fill, stroke }
     }
+
+    /// Returns the tolerance used to flatten the path in geometric queries:
+    /// the tolerance of the fill, or of the stroke.
+    pub(crate) fn tolerance(&self) -> f32 {
+        self.fill.map_or_else(
+            || {
+                self.stroke
+                    .map_or(tess::FillOptions::DEFAULT_TOLERANCE, |stroke| {
+                        stroke.options.tolerance
+                    })
+            },
+            |fill| fill.options.tolerance,
+        )
+    }
+
+    /// Scales and moves the path into `target`,
+    /// according to its bounding box.
+    ///
+    /// The stroke width is not taken into account,
+    /// nor scaled along with the path.
+    /// Gradients are not moved: they apply to the fitted geometry.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bevy::{color::palettes::css::*, prelude::*, sprite::Anchor};
+    /// use bevy_prototype_lyon::prelude::*;
+    ///
+    /// // Fit a 16×16 icon into a 64×64 box whose bottom left corner is at the
+    /// // origin.
+    /// let icon = shapes::Circle {
+    ///     radius: 8.0,
+    ///     ..default()
+    /// };
+    /// let mut shape = ShapeBuilder::with(&icon).fill(RED).build();
+    /// shape.fit(
+    ///     Rect::new(0.0, 0.0, 64.0, 64.0),
+    ///     FitMode::Contain,
+    ///     Anchor::Center,
+    /// );
+    /// ```
+    pub fn fit(&mut self, target: Rect, mode: FitMode, anchor: Anchor) {
+        if self.path.iter().next().is_none() {
+            return;
+        }
+        let bounds = bounding_box(self.path.iter()).to_rect();
+        let transform = mode.transform(bounds, target, anchor).to_transform();
+        self.path = self.path.clone().transformed(&transform);
+    }
+
+    /// Moves the path so that the `anchor` point of its bounding box
+    /// lies at the origin.
+    pub fn anchor(&mut self, anchor: Anchor) {
+        self.fit(Rect::default(), FitMode::None, anchor);
+    }
 }
 
 impl Geometry<Builder> for Shape {
@@ -61,7 +124,3 @@ impl Geometry<Builder> for Shape {
         b.extend_from_paths(&[self.path.as_slice()]);
     }
 }
-
-fn color_material_handle() -> MeshMaterial2d<ColorMaterial> {
-    MeshMaterial2d(COLOR_MATERIAL_HANDLE)
-}
@@ -0,0 +1,447 @@
+//! Extrusion of shapes into 3D prism meshes.
+//!
+//! The fill of a [`Shape`] is tessellated into a front and a back cap,
+//! joined by side walls built from its flattened outline.
+//! Walls are only generated along edges separating the inside of the fill
+//! from the outside, according to the fill rule,
+//! so holes become tunnels going through the prism.
+
+use bevy::{
+    color::{Color, ColorToComponents},
+    log::error,
+    math::{Vec2, Vec3},
+    render::{
+        mesh::{Indices, Mesh},
+        render_asset::RenderAssetUsages,
+        render_resource::PrimitiveTopology,
+    },
+};
+use lyon_algorithms::hit_test::hit_test_path;
+use lyon_tessellation::{
+    math::Point,
+    path::{iterator::PathIterator, Path, PathEvent},
+    BuffersBuilder, FillOptions, FillTessellator, FillVertex, VertexBuffers,
+};
+
+use crate::{draw::UvMapping, entity::Shape};
+
+/// Miter vectors longer than this are clamped, to avoid spikes at sharp
+/// corners of the bevel.
+const MITER_LIMIT: f32 = 4.0;
+
+/// Defines how the normals of the side walls of an extrusion are computed.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ExtrusionNormals {
+    /// Each face of the walls has its own normal, giving a faceted look.
+    #[default]
+    Flat,
+    /// Normals are averaged between adjacent faces of the walls.
+    Smooth,
+}
+
+/// A bevel rounding the edges between the caps and the walls of an
+/// extrusion.
+///
+/// The bevel grows outward from the outline of the caps,
+/// so it should be small compared to the features of the shape.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Bevel {
+    /// The distance the bevel extends outward and along the depth.
+    pub size: f32,
+    /// The number of faces of the bevel profile.
+    /// A single segment produces a chamfer.
+    pub segments: u32,
+}
+
+impl Bevel {
+    /// Creates a chamfer of the given size.
+    #[must_use]
+    pub fn chamfer(size: f32) -> Self {
+        Self { size, segments: 1 }
+    }
+
+    /// Creates a rounded bevel of the given size.
+    #[must_use]
+    pub fn round(size: f32, segments: u32) -> Self {
+        Self { size, segments }
+    }
+}
+
+/// Options for extruding the fill of a [`Shape`] into a prism [`Mesh`].
+///
+/// The caps lie on planes parallel to the `XY` plane,
+/// at `depth / 2.0` and `-depth / 2.0` along `Z`.
+/// The fill options (including the fill rule and the tolerance)
+/// and the color of the fill are used for the whole mesh.
+///
+/// The mesh has positions, normals, texture coordinates and vertex colors,
+/// so it can be drawn with a `StandardMaterial`.
+/// The texture coordinates of the caps follow the [`UvMapping`] of the fill,
+/// those of the walls go around each outline along `u`
+/// and from the front to the back along `v`.
+///
+/// # Example
+///
+/// ```
+/// use bevy::{color::palettes::css::*, prelude::*};
+/// use bevy_prototype_lyon::prelude::*;
+///
+/// let star = shapes::RegularPolygon {
+///     sides: 5,
+///     ..default()
+/// };
+/// let shape = ShapeBuilder::with(&star).fill(GOLD).build();
+/// let mesh = ExtrusionOptions::new(0.5)
+///     .with_bevel(Bevel::round(0.05, 4))
+///     .mesh(&shape);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ExtrusionOptions {
+    /// The distance between the front and the back caps.
+    pub depth: f32,
+    /// The bevel between the caps and the walls, if any.
+    pub bevel: Option<Bevel>,
+    /// How the normals of the walls are computed.
+    pub normals: ExtrusionNormals,
+}
+
+impl ExtrusionOptions {
+    /// Creates an extrusion of the given depth, without bevel.
+    #[must_use]
+    pub fn new(depth: f32) -> Self {
+        Self {
+            depth,
+            bevel: None,
+            normals: ExtrusionNormals::default(),
+        }
+    }
+
+    /// Sets the [`Bevel`] of the extrusion.
+    #[must_use]
+    pub fn with_bevel(mut self, bevel: Bevel) -> Self {
+        self.bevel = Some(bevel);
+        self
+    }
+
+    /// Sets how the normals of the walls are computed.
+    #[must_use]
+    pub fn with_normals(mut self, normals: ExtrusionNormals) -> Self {
+        self.normals = normals;
+        self
+    }
+
+    /// Builds the mesh of the extruded fill of `shape`.
+    ///
+    /// Shapes without a fill are extruded with the default [`FillOptions`].
+    #[must_use]
+    pub fn mesh(&self, shape: &Shape) -> Mesh {
+        let (options, color, uv) = shape.fill.as_ref().map_or_else(
+            || (FillOptions::default(), Color::WHITE, UvMapping::default()),
+            |fill| (fill.options, fill.color, fill.uv),
+        );
+
+        let contours = flatten(&shape.path, options.tolerance);
+        let flat = contours_to_path(&contours);
+        let mut builder = MeshBuilder::default();
+        let half_depth = self.depth / 2.0;
+
+        let uv = uv.to_affine(&shape.path);
+        let mut cap_buffers: VertexBuffers<Vec2, u32> = VertexBuffers::new();
+        if let Err(e) = FillTessellator::new().tessellate_path(
+            &flat,
+            &options,
+            &mut BuffersBuilder::new(&mut cap_buffers, |vertex: FillVertex| {
+                Vec2::new(vertex.position().x, vertex.position().y)
+            }),
+        ) {
+            error!("FillTessellator error: {:?}", e);
+        }
+        for (z, normal) in [(half_depth, Vec3::Z), (-half_depth, Vec3::NEG_Z)] {
+            let base = builder.positions.len() as u32;
+            for position in &cap_buffers.vertices {
+                builder.push(position.extend(z), normal, uv.transform_point2(*position));
+            }
+            for triangle in cap_buffers.indices.chunks_exact(3) {
+                builder.cap_triangle(
+                    [triangle[0], triangle[1], triangle[2]].map(|index| base + index),
+                    z,
+                );
+            }
+        }
+
+        let profile = self.profile();
+        for contour in &contours {
+            self.add_walls(&mut builder, contour, &flat, options, &profile);
+        }
+
+        builder.build(color)
+    }
+
+    /// Returns the rings of the side profile, from the front to the back cap.
+    ///
+    /// Each ring is made of the outward offset from the outline,
+    /// its `z` coordinate and the fraction of the profile length.
+    fn profile(&self) -> Vec<ProfileRing> {
+        let half_depth = self.depth / 2.0;
+        let mut front = vec![Vec2::new(0.0, half_depth)];
+        let bevel = self.bevel.filter(|b| b.size > 0.0 && b.segments > 0);
+        let segments = bevel.map_or(0, |b| b.segments);
+        let size = bevel.map_or(0.0, |b| b.size.min(half_depth));
+        front.extend((1..=segments).map(|k| {
+            let angle = k as f32 / segments as f32 * std::f32::consts::FRAC_PI_2;
+            Vec2::new(size * angle.sin(), half_depth - size * (1.0 - angle.cos()))
+        }));
+        // Without a straight wall, the back bevel starts where the front one
+        // ends.
+        let skip = usize::from(segments > 0 && size >= half_depth);
+        let back = front.iter().rev().skip(skip).map(|p| Vec2::new(p.x, -p.y));
+        let points: Vec<Vec2> = front.iter().copied().chain(back).collect();
+
+        let mut length = 0.0;
+        let mut rings: Vec<ProfileRing> = points
+            .iter()
+            .enumerate()
+            .map(|(i, p)| {
+                if i > 0 {
+                    length += p.distance(points[i - 1]);
+                }
+                ProfileRing {
+                    offset: p.x,
+                    z: p.y,
+                    v: length,
+                }
+            })
+            .collect();
+        if length > 0.0 {
+            for ring in &mut rings {
+                ring.v /= length;
+            }
+        }
+        rings
+    }
+
+    /// Adds the walls along the edges of a closed outline.
+    fn add_walls(
+        &self,
+        builder: &mut MeshBuilder,
+        contour: &[Vec2],
+        flat: &Path,
+        options: FillOptions,
+        profile: &[ProfileRing],
+    ) {
+        let n = contour.len();
+        let edge_normals = edge_normals(contour, flat, options);
+        let miters = miters(&edge_normals);
+
+        let mut distances = Vec::with_capacity(n + 1);
+        distances.push(0.0);
+        for i in 0..n {
+            distances.push(distances[i] + contour[i].distance(contour[(i + 1) % n]));
+        }
+        let perimeter = distances[n].max(f32::EPSILON);
+
+        for (i, normal) in edge_normals.iter().enumerate() {
+            let Some(normal) = *normal else {
+                continue;
+            };
+            let j = (i + 1) % n;
+            let outline_normal = |vertex: usize| match self.normals {
+                ExtrusionNormals::Flat => normal,
+                ExtrusionNormals::Smooth => miters[vertex].normalize_or(normal),
+            };
+            let (normal_a, normal_b) = (outline_normal(i), outline_normal(j));
+
+            for face in 0..profile.len() - 1 {
+                let base = builder.positions.len() as u32;
+                for k in [face, face + 1] {
+                    let ring = &profile[k];
+                    let profile_normal = match self.normals {
+                        ExtrusionNormals::Flat => face_normal(profile, face),
+                        ExtrusionNormals::Smooth => ring_normal(profile, k),
+                    };
+                    for (vertex, outward, u) in [
+                        (i, normal_a, distances[i] / perimeter),
+                        (j, normal_b, distances[i + 1] / perimeter),
+                    ] {
+                        let position = contour[vertex] + miters[vertex] * ring.offset;
+                        builder.push(
+                            position.extend(ring.z),
+                            (outward * profile_normal.x)
+                                .extend(profile_normal.y)
+                                .normalize_or_zero(),
+                            Vec2::new(u, ring.v),
+                        );
+                    }
+                }
+                builder.wall_triangle(base, base + 1, base + 3, normal);
+                builder.wall_triangle(base, base + 3, base + 2, normal);
+            }
+        }
+    }
+}
+
+/// Returns the outward normal of each edge of a closed outline,
+/// or `None` for edges that are not a boundary of the fill.
+fn edge_normals(contour: &[Vec2], flat: &Path, options: FillOptions) -> Vec<Option<Vec2>> {
+    let n = contour.len();
+    let inside =
+        |p: Vec2| hit_test_path(&Point::new(p.x, p.y), flat.iter(), options.fill_rule, 0.0);
+    (0..n)
+        .map(|i| {
+            let (a, b) = (contour[i], contour[(i + 1) % n]);
+            let direction = b - a;
+            let length = direction.length();
+            if length <= f32::EPSILON {
+                return None;
+            }
+            let left = direction.perp() / length;
+            let eps = (length * 0.01).min(options.tolerance * 0.1);
+            let mid = (a + b) / 2.0;
+            match (inside(mid + left * eps), inside(mid - left * eps)) {
+                (true, false) => Some(-left),
+                (false, true) => Some(left),
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+/// Returns the outward miter vector of each vertex of a closed outline,
+/// given the normals of its edges.
+fn miters(edge_normals: &[Option<Vec2>]) -> Vec<Vec2> {
+    let n = edge_normals.len();
+    (0..n)
+        .map(|i| match (edge_normals[(i + n - 1) % n], edge_normals[i]) {
+            (Some(previous), Some(next)) => {
+                let denominator = 1.0 + previous.dot(next);
+                if denominator <= f32::EPSILON {
+                    next
+                } else {
+                    ((previous + next) / denominator).clamp_length_max(MITER_LIMIT)
+                }
+            }
+            (Some(normal), None) | (None, Some(normal)) => normal,
+            (None, None) => Vec2::ZERO,
+        })
+        .collect()
+}
+
+/// A ring of the side profile of an extrusion.
+struct ProfileRing {
+    offset: f32,
+    z: f32,
+    v: f32,
+}
+
+/// Returns the normal of the face of the profile between rings `face` and
+/// `face + 1`, as an outward component and a `z` component.
+fn face_normal(profile: &[ProfileRing], face: usize) -> Vec2 {
+    let (a, b) = (&profile[face], &profile[face + 1]);
+    Vec2::new(a.z - b.z, b.offset - a.offset).normalize_or_zero()
+}
+
+/// Returns the normal of the profile at ring `k`,
+/// averaged between the faces it belongs to.
+fn ring_normal(profile: &[ProfileRing], k: usize) -> Vec2 {
+    let last = profile.len() - 2;
+    if k == 0 {
+        face_normal(profile, 0)
+    } else if k > last {
+        face_normal(profile, last)
+    } else {
+        (face_normal(profile, k - 1) + face_normal(profile, k)).normalize_or_zero()
+    }
+}
+
+/// Flattens the subpaths of `path` into closed polylines.
+fn flatten(path: &Path, tolerance: f32) -> Vec<Vec<Vec2>> {
+    let mut contours = Vec::new();
+    let mut current: Vec<Vec2> = Vec::new();
+    for event in path.iter().flattened(tolerance) {
+        match event {
+            PathEvent::Begin { at } => {
+                current = vec![Vec2::new(at.x, at.y)];
+            }
+            PathEvent::Line { to, .. } => {
+                let to = Vec2::new(to.x, to.y);
+                if current.last() != Some(&to) {
+                    current.push(to);
+                }
+            }
+            PathEvent::End { .. } => {
+                if current.len() > 1 && current.first() == current.last() {
+                    current.pop();
+                }
+                if current.len() > 2 {
+                    contours.push(std::mem::take(&mut current));
+                }
+            }
+            _ => {}
+        }
+    }
+    contours
+}
+
+/// Builds a path of closed polygons from flattened outlines.
+fn contours_to_path(contours: &[Vec<Vec2>]) -> Path {
+    let mut builder = Path::builder();
+    for contour in contours {
+        builder.add_polygon(lyon_tessellation::path::Polygon {
+            points: &contour
+                .iter()
+                .map(|p| Point::new(p.x, p.y))
+                .collect::<Vec<_>>(),
+            closed: true,
+        });
+    }
+    builder.build()
+}
+
+#[derive(Default)]
+struct MeshBuilder {
+    positions: Vec<[f32; 3]>,
+    normals: Vec<[f32; 3]>,
+    uvs: Vec<[f32; 2]>,
+    indices: Vec<u32>,
+}
+
+impl MeshBuilder {
+    fn push(&mut self, position: Vec3, normal: Vec3, uv: Vec2) {
+        self.positions.push(position.to_array());
+        self.normals.push(normal.to_array());
+        self.uvs.push(uv.to_array());
+    }
+
+    /// Adds a triangle of a cap, facing towards the sign of `z`.
+    fn cap_triangle(&mut self, [a, b, c]: [u32; 3], z: f32) {
+        let [pa, pb, pc] = [a, b, c].map(|i| Vec3::from(self.positions[i as usize]));
+        if (pb - pa).cross(pc - pa).z * z >= 0.0 {
+            self.indices.extend([a, b, c]);
+        } else {
+            self.indices.extend([a, c, b]);
+        }
+    }
+
+    /// Adds a triangle of a wall, facing `outward` on the `XY` plane.
+    fn wall_triangle(&mut self, a: u32, b: u32, c: u32, outward: Vec2) {
+        let [pa, pb, pc] = [a, b, c].map(|i| Vec3::from(self.positions[i as usize]));
+        if (pb - pa).cross(pc - pa).truncate().dot(outward) >= 0.0 {
+            self.indices.extend([a, b, c]);
+        } else {
+            self.indices.extend([a, c, b]);
+        }
+    }
+
+    fn build(self, color: Color) -> Mesh {
+        let colors = vec![color.to_linear().to_f32_array(); self.positions.len()];
+        Mesh::new(
+            PrimitiveTopology::TriangleList,
+            RenderAssetUsages::default(),
+        )
+        .with_inserted_indices(Indices::U32(self.indices))
+        .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, self.positions)
+        .with_inserted_attribute(Mesh::ATTRIBUTE_NORMAL, self.normals)
+        .with_inserted_attribute(Mesh::ATTRIBUTE_UV_0, self.uvs)
+        .with_inserted_attribute(Mesh::ATTRIBUTE_COLOR, colors)
+    }
+}
@@ -0,0 +1,63 @@
+//! Fitting of geometry into a target box.
+//!
+//! Shapes can be fitted with [`Shape::fit`](crate::entity::Shape::fit)
+//! or [`ReadyShapeBuilder::fit`](crate::geometry::ReadyShapeBuilder::fit),
+//! regardless of the origin convention of their geometry.
+
+use bevy::{
+    math::{Affine2, Rect, Vec2},
+    sprite::Anchor,
+};
+
+/// Defines how geometry is scaled to fit into a target box,
+/// similarly to the CSS `object-fit` property.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum FitMode {
+    /// The geometry is scaled uniformly to fit entirely into the box.
+    #[default]
+    Contain,
+    /// The geometry is scaled uniformly to cover the whole box,
+    /// possibly overflowing it.
+    Cover,
+    /// The geometry is stretched to match the box exactly,
+    /// changing its aspect ratio.
+    Fill,
+    /// The geometry is not scaled.
+    None,
+}
+
+impl FitMode {
+    /// Returns the transform that maps `bounds` into `target`.
+    ///
+    /// The `anchor` point of the scaled `bounds` is placed on the `anchor`
+    /// point of `target`, like the CSS `object-position` property.
+    /// Empty dimensions of `bounds` are not scaled.
+    #[must_use]
+    pub fn transform(self, bounds: Rect, target: Rect, anchor: Anchor) -> Affine2 {
+        let size = bounds.size();
+        let ratio = Vec2::new(
+            if size.x > 0.0 {
+                target.width() / size.x
+            } else {
+                f32::NAN
+            },
+            if size.y > 0.0 {
+                target.height() / size.y
+            } else {
+                f32::NAN
+            },
+        );
+        // `f32::min` and `f32::max` ignore the `NaN` of empty dimensions.
+        let scale = match self {
+            Self::Contain => Vec2::splat(ratio.x.min(ratio.y)),
+            Self::Cover => Vec2::splat(ratio.x.max(ratio.y)),
+            Self::Fill => ratio,
+            Self::None => Vec2::ONE,
+        };
+        let scale = Vec2::select(scale.is_nan_mask(), Vec2::ONE, scale);
+        let anchor = anchor.as_vec();
+        let from = bounds.center() + anchor * size;
+        let to = target.center() + anchor * target.size();
+        Affine2::from_scale_angle_translation(scale, 0.0, to - from * scale)
+    }
+}
@@ -0,0 +1,369 @@
+//! Movement of entities along paths.
+//!
+//! The [`FollowPath`] component moves an entity along the path of a shape,
+//! or along any measured path.
+//! Shapes that are followed get a [`ShapeMeasure`],
+//! shared by all their followers and updated when their path changes.
+
+use std::{
+    hash::{DefaultHasher, Hash, Hasher},
+    sync::Arc,
+};
+
+use bevy::{prelude::*, utils::HashMap};
+use lyon_tessellation::path::PathEvent;
+
+use crate::{entity::Shape, measure::PathMeasure};
+
+/// Path followed by a [`FollowPath`].
+#[derive(Clone)]
+pub enum FollowTarget {
+    /// The path of a shape entity.
+    Shape(Entity),
+    /// A measured path.
+    Path(Arc<PathMeasure>),
+}
+
+impl From<Entity> for FollowTarget {
+    fn from(entity: Entity) -> Self {
+        Self::Shape(entity)
+    }
+}
+
+impl From<PathMeasure> for FollowTarget {
+    fn from(measure: PathMeasure) -> Self {
+        Self::Path(Arc::new(measure))
+    }
+}
+
+/// Behavior of a [`FollowPath`] at the ends of the path.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum FollowMode {
+    /// The movement starts over from the other end.
+    #[default]
+    Loop,
+    /// The movement stops.
+    Once,
+    /// The movement reverses its direction.
+    PingPong,
+}
+
+/// `Component` moving an entity along a path.
+///
+/// The translation of the entity is set in the space of the path,
+/// keeping its Z coordinate,
+/// so followers of a shape are usually spawned as its children.
+///
+/// # Example
+///
+/// ```
+/// use bevy::{color::palettes::css::*, prelude::*};
+/// use bevy_prototype_lyon::prelude::*;
+///
+/// fn setup_system(mut commands: Commands) {
+///     let track = shapes::Circle {
+///         radius: 200.0,
+///         ..default()
+///     };
+///     let train = shapes::Rectangle {
+///         extents: Vec2::new(40.0, 20.0),
+///         ..default()
+///     };
+///
+///     commands
+///         .spawn(ShapeBuilder::with(&track).stroke((BLACK, 4.0)).build())
+///         .with_children(|parent| {
+///             let track = parent.parent_entity();
+///             parent.spawn((
+///                 ShapeBuilder::with(&train).fill(RED).build(),
+///                 FollowPath::new(track).with_speed(100.0).with_rotation(true),
+///             ));
+///         });
+/// }
+/// ```
+#[derive(Component, Clone)]
+#[require(Transform)]
+pub struct FollowPath {
+    /// Path to follow.
+    pub target: FollowTarget,
+    /// Current distance from the beginning of the path.
+    ///
+    /// In [`FollowMode::PingPong`], distances between the length of the path
+    /// and twice that length are on the way back.
+    pub distance: f32,
+    /// Distance covered per second.
+    ///
+    /// Negative speeds move backwards.
+    pub speed: f32,
+    /// Behavior at the ends of the path.
+    pub mode: FollowMode,
+    /// Whether the X axis of the entity is rotated along the path.
+    pub rotate: bool,
+    /// Whether `distance` and `speed` are fractions of the length of the
+    /// path, instead of units of length.
+    pub normalized: bool,
+}
+
+impl FollowPath {
+    /// Creates a component following `target` from its beginning,
+    /// without moving.
+    #[must_use]
+    pub fn new(target: impl Into<FollowTarget>) -> Self {
+        Self {
+            target: target.into(),
+            distance: 0.0,
+            speed: 0.0,
+            mode: FollowMode::default(),
+            rotate: false,
+            normalized: false,
+        }
+    }
+
+    /// Sets the distance from the beginning of the path.
+    #[must_use]
+    pub fn with_distance(mut self, distance: f32) -> Self {
+        self.distance = distance;
+        self
+    }
+
+    /// Sets the speed.
+    #[must_use]
+    pub fn with_speed(mut self, speed: f32) -> Self {
+        self.speed = speed;
+        self
+    }
+
+    /// Sets the behavior at the ends of the path.
+    #[must_use]
+    pub fn with_mode(mut self, mode: FollowMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Sets whether the entity is rotated along the path.
+    #[must_use]
+    pub fn with_rotation(mut self, rotate: bool) -> Self {
+        self.rotate = rotate;
+        self
+    }
+
+    /// Sets whether the distance and the speed are fractions of the length
+    /// of the path.
+    #[must_use]
+    pub fn with_normalized(mut self, normalized: bool) -> Self {
+        self.normalized = normalized;
+        self
+    }
+
+    /// Moves the distance by `delta`, according to the mode,
+    /// on a path of the given `length`.
+    fn advance(&mut self, delta: f32, length: f32) {
+        let distance = self.distance + delta;
+        self.distance = match self.mode {
+            FollowMode::Loop => distance.rem_euclid(length),
+            FollowMode::Once => distance.clamp(0.0, length),
+            // One back and forth cycle, folded by `position`.
+            FollowMode::PingPong => distance.rem_euclid(2.0 * length),
+        };
+    }
+
+    /// Returns the distance of the entity from the beginning of a path
+    /// of the given `length`.
+    ///
+    /// In [`FollowMode::PingPong`], the entity goes backwards on odd laps.
+    fn position(&self, length: f32) -> f32 {
+        match self.mode {
+            FollowMode::PingPong if length > 0.0 => {
+                let lap = (self.distance / length).floor();
+                let along = self.distance.rem_euclid(length);
+                if lap.rem_euclid(2.0) >= 1.0 {
+                    length - along
+                } else {
+                    along
+                }
+            }
+            _ => self.distance,
+        }
+    }
+
+    /// Moves by `delta_secs` of movement on a path of the given `length`,
+    /// and returns the distance of the entity from the beginning of the path.
+    fn update(&mut self, delta_secs: f32, length: f32) -> f32 {
+        let scale = if self.normalized { length } else { 1.0 };
+        if length > 0.0 && self.speed != 0.0 {
+            self.advance(self.speed * delta_secs, length / scale);
+        }
+        self.position(length / scale) * scale
+    }
+}
+
+/// `Component` caching the measurements of the path of a [`Shape`],
+/// added to shapes followed by a [`FollowPath`].
+///
+/// The path is measured again only when its geometry or tolerance change,
+/// not when the shape is only repainted.
+#[derive(Component, Clone)]
+pub struct ShapeMeasure {
+    measure: Arc<PathMeasure>,
+    /// Hash of the measured path and tolerance.
+    key: u64,
+}
+
+impl ShapeMeasure {
+    /// Returns the measurements of the path.
+    #[must_use]
+    pub fn measure(&self) -> &PathMeasure {
+        &self.measure
+    }
+}
+
+/// Returns a hash of the geometry and the tolerance of `shape`,
+/// which determine its measurements.
+fn measure_key(shape: &Shape) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    shape.tolerance().to_bits().hash(&mut hasher);
+    for event in &shape.path {
+        let (verb, points) = match event {
+            PathEvent::Begin { at } => (0, [Some(at), None, None]),
+            PathEvent::Line { to, .. } => (1, [Some(to), None, None]),
+            PathEvent::Quadratic { ctrl, to, .. } => (2, [Some(ctrl), Some(to), None]),
+            PathEvent::Cubic {
+                ctrl1, ctrl2, to, ..
+            } => (3, [Some(ctrl1), Some(ctrl2), Some(to)]),
+            PathEvent::End { close, .. } => (4 + u8::from(close), [None; 3]),
+        };
+        verb.hash(&mut hasher);
+        for point in points.into_iter().flatten() {
+            point.x.to_bits().hash(&mut hasher);
+            point.y.to_bits().hash(&mut hasher);
+        }
+    }
+    hasher.finish()
+}
+
+/// Moves the entities with a [`FollowPath`] along their paths.
+pub(crate) fn follow_path_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut followers: Query<(&mut FollowPath, &mut Transform)>,
+    shapes: Query<(Ref<Shape>, Option<&ShapeMeasure>)>,
+) {
+    // Measurements made during this run, shared by followers of the same
+    // shape.
+    let mut measured = HashMap::new();
+    for (mut follow, mut transform) in &mut followers {
+        let measure = match &follow.target {
+            FollowTarget::Path(measure) => measure.clone(),
+            FollowTarget::Shape(entity) => {
+                let Ok((shape, cached)) = shapes.get(*entity) else {
+                    continue;
+                };
+                match cached {
+                    Some(cached) if !shape.is_changed() => cached.measure.clone(),
+                    _ => measured
+                        .entry(*entity)
+                        .or_insert_with(|| {
+                            let key = measure_key(&shape);
+                            if let Some(cached) = cached.filter(|cached| cached.key == key) {
+                                return cached.measure.clone();
+                            }
+                            let measure = Arc::new(shape.measure());
+                            commands.entity(*entity).insert(ShapeMeasure {
+                                measure: measure.clone(),
+                                key,
+                            });
+                            measure
+                        })
+                        .clone(),
+                }
+            }
+        };
+
+        let distance = follow.update(time.delta_secs(), measure.length());
+        let Some(sample) = measure.sample(distance) else {
+            continue;
+        };
+        transform.translation = sample.position.extend(transform.translation.z);
+        if follow.rotate {
+            transform.rotation = Quat::from_rotation_z(sample.tangent.to_angle());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LENGTH: f32 = 10.0;
+
+    fn follower(mode: FollowMode, speed: f32) -> FollowPath {
+        FollowPath::new(Entity::PLACEHOLDER)
+            .with_mode(mode)
+            .with_speed(speed)
+    }
+
+    /// Returns the positions after each step of one second.
+    fn positions(follow: &mut FollowPath, steps: usize) -> Vec<f32> {
+        (0..steps).map(|_| follow.update(1.0, LENGTH)).collect()
+    }
+
+    #[test]
+    fn loop_wraps_around() {
+        let mut follow = follower(FollowMode::Loop, 4.0);
+        assert_eq!(positions(&mut follow, 4), [4.0, 8.0, 2.0, 6.0]);
+    }
+
+    #[test]
+    fn once_stops_at_the_end() {
+        let mut follow = follower(FollowMode::Once, 4.0);
+        assert_eq!(positions(&mut follow, 4), [4.0, 8.0, 10.0, 10.0]);
+        assert!((follow.speed - 4.0).abs() <= f32::EPSILON);
+    }
+
+    #[test]
+    fn ping_pong_reverses_at_the_ends() {
+        let mut follow = follower(FollowMode::PingPong, 4.0);
+        assert_eq!(
+            positions(&mut follow, 7),
+            [4.0, 8.0, 8.0, 4.0, 0.0, 4.0, 8.0]
+        );
+        // The direction is kept in the distance, not in the speed.
+        assert!((follow.speed - 4.0).abs() <= f32::EPSILON);
+    }
+
+    #[test]
+    fn ping_pong_handles_steps_longer_than_the_path() {
+        let mut follow = follower(FollowMode::PingPong, 25.0);
+        assert_eq!(positions(&mut follow, 4), [5.0, 10.0, 5.0, 0.0]);
+    }
+
+    #[test]
+    fn negative_speed_moves_backwards() {
+        let mut follow = follower(FollowMode::Loop, -4.0);
+        assert_eq!(positions(&mut follow, 3), [6.0, 2.0, 8.0]);
+
+        let mut follow = follower(FollowMode::Once, -4.0).with_distance(6.0);
+        assert_eq!(positions(&mut follow, 2), [2.0, 0.0]);
+
+        let mut follow = follower(FollowMode::PingPong, -4.0);
+        assert_eq!(positions(&mut follow, 4), [4.0, 8.0, 8.0, 4.0]);
+    }
+
+    #[test]
+    fn normalized_distance_is_a_fraction_of_the_length() {
+        let mut follow = follower(FollowMode::Loop, 0.25)
+            .with_distance(0.5)
+            .with_normalized(true);
+        assert_eq!(positions(&mut follow, 3), [7.5, 0.0, 2.5]);
+        assert!((follow.distance - 0.25).abs() <= f32::EPSILON);
+
+        let mut follow = follower(FollowMode::PingPong, 0.75).with_normalized(true);
+        assert_eq!(positions(&mut follow, 2), [7.5, 5.0]);
+    }
+
+    #[test]
+    fn empty_path_does_not_move() {
+        let mut follow = follower(FollowMode::PingPong, 4.0).with_distance(1.0);
+        assert!((follow.update(1.0, 0.0) - 1.0).abs() <= f32::EPSILON);
+    }
+}
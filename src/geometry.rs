@@ -4,12 +4,14 @@
 //! plus a couple of builders and associated traits.
 //! To build any shape, start with [`ShapeBuilder`].
 
+use bevy::{math::Rect, sprite::Anchor};
 use lyon_algorithms::path::{builder::WithSvg, traits::Build, BuilderImpl};
 use lyon_tessellation::path::path::Builder;
 
 use crate::{
     draw::{Fill, Stroke},
     entity::Shape,
+    fit::FitMode,
 };
 
 /// Interface for defining the geometry of a shape.
@@ -95,6 +97,7 @@ impl<GenericBuilder> ShapeBuilderBase<GenericBuilder> for ShapeBuilder<GenericBu
             builder: self.0,
             fill: Some(fill.into()),
             stroke: None,
+            fit: None,
         }
     }
 
@@ -103,6 +106,7 @@ impl<GenericBuilder> ShapeBuilderBase<GenericBuilder> for ShapeBuilder<GenericBu
             builder: self.0,
             fill: None,
             stroke: Some(stroke.into()),
+            fit: None,
         }
     }
 }
@@ -132,6 +136,7 @@ pub struct ReadyShapeBuilder<GenericBuilder> {
     pub(crate) builder: GenericBuilder,
     pub(crate) fill: Option<Fill>,
     pub(crate) stroke: Option<Stroke>,
+    pub(crate) fit: Option<(Rect, FitMode, Anchor)>,
 }
 
 impl<GenericBuilder> ShapeBuilderBase<GenericBuilder> for ReadyShapeBuilder<GenericBuilder> {
@@ -159,9 +164,33 @@ impl<GenericBuilder> ReadyShapeBuilder<GenericBuilder>
 where
     GenericBuilder: Build<PathType = lyon_tessellation::path::Path>,
 {
+    /// Fits the geometry into `target` when building the [`Shape`].
+    ///
+    /// See [`Shape::fit`].
+    #[must_use]
+    pub fn fit(self, target: Rect, mode: FitMode, anchor: Anchor) -> Self {
+        Self {
+            fit: Some((target, mode, anchor)),
+            ..self
+        }
+    }
+
+    /// Moves the `anchor` point of the geometry to the origin
+    /// when building the [`Shape`].
+    ///
+    /// See [`Shape::anchor`].
+    #[must_use]
+    pub fn anchor(self, anchor: Anchor) -> Self {
+        self.fit(Rect::default(), FitMode::None, anchor)
+    }
+
     /// Builds a [`Shape`] according to builder settings.
     pub fn build(self) -> Shape {
-        Shape::new(self.builder.build(), self.fill, self.stroke)
+        let mut shape = Shape::new(self.builder.build(), self.fill, self.stroke);
+        if let Some((target, mode, anchor)) = self.fit {
+            shape.fit(target, mode, anchor);
+        }
+        shape
     }
 }
 
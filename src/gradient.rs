@@ -0,0 +1,352 @@
+//! Gradient paints and the material that renders them.
+//!
+//! Vertex colors can only be interpolated linearly across a triangle,
+//! so gradients are evaluated per fragment by the [`GradientMaterial`].
+//! A [`Shape`](crate::entity::Shape) whose [`Fill`](crate::draw::Fill)
+//! or [`Stroke`](crate::draw::Stroke) has a gradient
+//! is given this material automatically by the
+//! [`ShapePlugin`](crate::plugin::ShapePlugin).
+
+use bevy::{
+    asset::load_internal_asset,
+    color::Color,
+    math::Vec2,
+    prelude::*,
+    render::{
+        mesh::{MeshVertexAttribute, MeshVertexBufferLayoutRef},
+        render_asset::RenderAssets,
+        render_resource::{
+            AsBindGroup, AsBindGroupShaderType, RenderPipelineDescriptor, ShaderRef,
+            SpecializedMeshPipelineError, VertexFormat,
+        },
+        texture::GpuImage,
+    },
+    sprite::{AlphaMode2d, Material2d, Material2dKey},
+};
+pub use uniform::{GradientMaterialUniform, GradientUniform};
+
+const GRADIENT_SHADER_HANDLE: Handle<Shader> =
+    Handle::weak_from_u128(0x3F0B_7D52_9E41_4C8A_A1D6_5B2E_0C97_1E34);
+
+/// Shader importable as `bevy_prototype_lyon::gradient`,
+/// which evaluates gradients for the shaders of the crate.
+const GRADIENT_FUNCTIONS_SHADER_HANDLE: Handle<Shader> =
+    Handle::weak_from_u128(0x91C4_2E6A_57D3_4B0F_8E1A_C6F2_3D94_A075);
+
+/// The maximum number of color stops a [`Gradient`] can hold.
+pub const MAX_GRADIENT_STOPS: usize = 8;
+
+/// Vertex attribute holding the local-space position of a vertex
+/// and the gradient slot it is painted with.
+///
+/// The slot is `0.0` for solid-colored vertices,
+/// `1.0` for vertices painted with the fill gradient
+/// and `2.0` for vertices painted with the stroke gradient.
+pub const ATTRIBUTE_GRADIENT: MeshVertexAttribute =
+    MeshVertexAttribute::new("Vertex_Gradient", 0x5A7E_61C3, VertexFormat::Float32x3);
+
+/// The geometry of a [`Gradient`], in the local space of the shape.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GradientKind {
+    /// Colors vary along the line from `start` to `end`.
+    Linear {
+        /// The point where the gradient offset is `0.0`.
+        start: Vec2,
+        /// The point where the gradient offset is `1.0`.
+        end: Vec2,
+    },
+    /// Colors vary with the distance from `center`.
+    Radial {
+        /// The point where the gradient offset is `0.0`.
+        center: Vec2,
+        /// The distance from `center` where the gradient offset is `1.0`.
+        radius: f32,
+    },
+    /// Colors vary with the angle around `center`, counterclockwise.
+    Conic {
+        /// The point the gradient revolves around.
+        center: Vec2,
+        /// The angle, in radians, where the gradient offset is `0.0`.
+        angle: f32,
+    },
+}
+
+/// Defines how a [`Gradient`] is painted outside of the `0.0..=1.0` offset
+/// range.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum SpreadMode {
+    /// The colors of the first and last stops extend indefinitely.
+    #[default]
+    Pad,
+    /// The gradient restarts from the first stop.
+    Repeat,
+    /// The gradient alternates its direction at each repetition.
+    Reflect,
+}
+
+/// A color at a given offset of a [`Gradient`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GradientStop {
+    /// The position of the stop, usually between `0.0` and `1.0`.
+    pub offset: f32,
+    /// The color of the gradient at `offset`.
+    pub color: Color,
+}
+
+impl Default for GradientStop {
+    fn default() -> Self {
+        Self {
+            offset: 0.0,
+            color: Color::NONE,
+        }
+    }
+}
+
+/// A paint whose color varies smoothly across the shape.
+///
+/// A gradient can hold up to [`MAX_GRADIENT_STOPS`] color stops,
+/// which must be added in increasing offset order.
+///
+/// # Example
+///
+/// ```
+/// use bevy::{color::palettes::css::*, prelude::*};
+/// use bevy_prototype_lyon::prelude::*;
+///
+/// let gradient = Gradient::linear(Vec2::new(-100.0, 0.0), Vec2::new(100.0, 0.0))
+///     .with_stop(0.0, RED)
+///     .with_stop(1.0, BLUE)
+///     .with_spread(SpreadMode::Reflect);
+/// let fill = Fill::gradient(gradient);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Gradient {
+    /// The geometry of the gradient.
+    pub kind: GradientKind,
+    /// How the gradient is painted outside of its range.
+    pub spread: SpreadMode,
+    stops: [GradientStop; MAX_GRADIENT_STOPS],
+    len: usize,
+}
+
+impl Gradient {
+    /// Creates a gradient with the given geometry and no color stops.
+    #[must_use]
+    pub fn new(kind: GradientKind) -> Self {
+        Self {
+            kind,
+            spread: SpreadMode::default(),
+            stops: [GradientStop::default(); MAX_GRADIENT_STOPS],
+            len: 0,
+        }
+    }
+
+    /// Creates a linear gradient going from `start` to `end`.
+    #[must_use]
+    pub fn linear(start: Vec2, end: Vec2) -> Self {
+        Self::new(GradientKind::Linear { start, end })
+    }
+
+    /// Creates a radial gradient centered at `center`.
+    #[must_use]
+    pub fn radial(center: Vec2, radius: f32) -> Self {
+        Self::new(GradientKind::Radial { center, radius })
+    }
+
+    /// Creates a conic gradient revolving around `center`,
+    /// starting at `angle` radians.
+    #[must_use]
+    pub fn conic(center: Vec2, angle: f32) -> Self {
+        Self::new(GradientKind::Conic { center, angle })
+    }
+
+    /// Sets the [`SpreadMode`] of the gradient.
+    #[must_use]
+    pub fn with_spread(mut self, spread: SpreadMode) -> Self {
+        self.spread = spread;
+        self
+    }
+
+    /// Adds a color stop to the gradient.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the gradient already has [`MAX_GRADIENT_STOPS`] stops.
+    #[must_use]
+    pub fn with_stop(mut self, offset: f32, color: impl Into<Color>) -> Self {
+        assert!(
+            self.len < MAX_GRADIENT_STOPS,
+            "Gradients can have at most {MAX_GRADIENT_STOPS} stops"
+        );
+        self.stops[self.len] = GradientStop {
+            offset,
+            color: color.into(),
+        };
+        self.len += 1;
+        self
+    }
+
+    /// Returns the color stops of the gradient.
+    #[must_use]
+    pub fn stops(&self) -> &[GradientStop] {
+        &self.stops[..self.len]
+    }
+
+    /// Returns the color of the first stop,
+    /// or transparent if there are no stops.
+    ///
+    /// Used as the vertex color of the gradient,
+    /// for materials that do not evaluate it.
+    pub(crate) fn first_color(&self) -> Color {
+        self.stops().first().map_or(Color::NONE, |stop| stop.color)
+    }
+}
+
+/// Loads the shaders evaluating gradients.
+pub(crate) fn load_shaders(app: &mut App) {
+    load_internal_asset!(
+        app,
+        GRADIENT_FUNCTIONS_SHADER_HANDLE,
+        "gradient_functions.wgsl",
+        Shader::from_wgsl
+    );
+    load_internal_asset!(
+        app,
+        GRADIENT_SHADER_HANDLE,
+        "gradient.wgsl",
+        Shader::from_wgsl
+    );
+}
+
+/// A [`Material2d`] that paints the fill and the stroke of a shape
+/// with per-fragment gradients.
+///
+/// Vertices whose [`ATTRIBUTE_GRADIENT`] slot is `0.0` are painted with their
+/// vertex color.
+///
+/// Shapes painted with a gradient are given a `GradientMaterial` kept in sync
+/// with their paints, unless they already have a material.
+/// A `GradientMaterial` added by the user is never modified by the plugin.
+#[derive(Asset, AsBindGroup, TypePath, Debug, Clone, Default)]
+#[uniform(0, GradientMaterialUniform)]
+pub struct GradientMaterial {
+    /// The gradient used to paint the fill.
+    pub fill: Option<Gradient>,
+    /// The gradient used to paint the stroke.
+    pub stroke: Option<Gradient>,
+}
+
+impl Material2d for GradientMaterial {
+    fn vertex_shader() -> ShaderRef {
+        GRADIENT_SHADER_HANDLE.into()
+    }
+
+    fn fragment_shader() -> ShaderRef {
+        GRADIENT_SHADER_HANDLE.into()
+    }
+
+    fn alpha_mode(&self) -> AlphaMode2d {
+        AlphaMode2d::Blend
+    }
+
+    fn specialize(
+        descriptor: &mut RenderPipelineDescriptor,
+        layout: &MeshVertexBufferLayoutRef,
+        _key: Material2dKey<Self>,
+    ) -> Result<(), SpecializedMeshPipelineError> {
+        let vertex_layout = layout.0.get_layout(&[
+            Mesh::ATTRIBUTE_POSITION.at_shader_location(0),
+            Mesh::ATTRIBUTE_COLOR.at_shader_location(1),
+            ATTRIBUTE_GRADIENT.at_shader_location(2),
+        ])?;
+        descriptor.vertex.buffers = vec![vertex_layout];
+        Ok(())
+    }
+}
+
+#[allow(dead_code)] // False positives with `ShaderType`.
+mod uniform {
+    use bevy::{
+        color::{ColorToComponents, LinearRgba},
+        math::Vec4,
+        render::render_resource::ShaderType,
+    };
+
+    use super::{Gradient, GradientKind, SpreadMode, MAX_GRADIENT_STOPS};
+
+    /// The GPU representation of a [`Gradient`].
+    #[derive(Clone, Default, ShaderType)]
+    pub struct GradientUniform {
+        /// Linear: `start.xy, end.xy`. Radial: `center.xy, radius`.
+        /// Conic: `center.xy, angle`.
+        pub params: Vec4,
+        /// Linear colors of the stops.
+        pub colors: [Vec4; MAX_GRADIENT_STOPS],
+        /// Offsets of the stops, packed four per vector.
+        pub offsets: [Vec4; MAX_GRADIENT_STOPS / 4],
+        /// `0` for linear, `1` for radial, `2` for conic.
+        pub kind: u32,
+        /// `0` for pad, `1` for repeat, `2` for reflect.
+        pub spread: u32,
+        /// Number of stops in use.
+        pub len: u32,
+    }
+
+    impl From<&Gradient> for GradientUniform {
+        fn from(gradient: &Gradient) -> Self {
+            let (kind, params) = match gradient.kind {
+                GradientKind::Linear { start, end } => {
+                    (0, Vec4::new(start.x, start.y, end.x, end.y))
+                }
+                GradientKind::Radial { center, radius } => {
+                    (1, Vec4::new(center.x, center.y, radius, 0.0))
+                }
+                GradientKind::Conic { center, angle } => {
+                    (2, Vec4::new(center.x, center.y, angle, 0.0))
+                }
+            };
+            let spread = match gradient.spread {
+                SpreadMode::Pad => 0,
+                SpreadMode::Repeat => 1,
+                SpreadMode::Reflect => 2,
+            };
+            let mut colors = [Vec4::ZERO; MAX_GRADIENT_STOPS];
+            let mut offsets = [Vec4::ZERO; MAX_GRADIENT_STOPS / 4];
+            for (i, stop) in gradient.stops().iter().enumerate() {
+                colors[i] = LinearRgba::from(stop.color).to_vec4();
+                offsets[i / 4][i % 4] = stop.offset;
+            }
+
+            Self {
+                params,
+                colors,
+                offsets,
+                kind,
+                spread,
+                len: gradient.len as u32,
+            }
+        }
+    }
+
+    /// The GPU representation of the uniform data of a
+    /// [`GradientMaterial`](super::GradientMaterial).
+    #[derive(Clone, Default, ShaderType)]
+    pub struct GradientMaterialUniform {
+        /// The fill gradient.
+        pub fill: GradientUniform,
+        /// The stroke gradient.
+        pub stroke: GradientUniform,
+    }
+}
+
+impl AsBindGroupShaderType<GradientMaterialUniform> for GradientMaterial {
+    fn as_bind_group_shader_type(
+        &self,
+        _images: &RenderAssets<GpuImage>,
+    ) -> GradientMaterialUniform {
+        GradientMaterialUniform {
+            fill: self.fill.as_ref().map(Into::into).unwrap_or_default(),
+            stroke: self.stroke.as_ref().map(Into::into).unwrap_or_default(),
+        }
+    }
+}
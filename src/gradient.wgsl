@@ -0,0 +1,60 @@
+#import bevy_sprite::{
+    mesh2d_functions as mesh_functions,
+    mesh2d_view_bindings::view,
+}
+#import bevy_prototype_lyon::gradient::{Gradient, gradient_color}
+
+#ifdef TONEMAP_IN_SHADER
+#import bevy_core_pipeline::tonemapping
+#endif
+
+struct GradientMaterial {
+    fill: Gradient,
+    stroke: Gradient,
+};
+
+@group(2) @binding(0) var<uniform> material: GradientMaterial;
+
+struct Vertex {
+    @builtin(instance_index) instance_index: u32,
+    @location(0) position: vec3<f32>,
+    @location(1) color: vec4<f32>,
+    @location(2) gradient: vec3<f32>,
+};
+
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) color: vec4<f32>,
+    @location(1) local_position: vec2<f32>,
+    @location(2) @interpolate(flat) slot: u32,
+};
+
+@vertex
+fn vertex(vertex: Vertex) -> VertexOutput {
+    var out: VertexOutput;
+    let world_from_local = mesh_functions::get_world_from_local(vertex.instance_index);
+    let world_position = mesh_functions::mesh2d_position_local_to_world(
+        world_from_local,
+        vec4<f32>(vertex.position, 1.0)
+    );
+    out.position = mesh_functions::mesh2d_position_world_to_clip(world_position);
+    out.color = vertex.color;
+    out.local_position = vertex.gradient.xy;
+    out.slot = u32(round(vertex.gradient.z));
+    return out;
+}
+
+@fragment
+fn fragment(in: VertexOutput) -> @location(0) vec4<f32> {
+    var color = in.color;
+    if in.slot == 1u {
+        color = gradient_color(material.fill, in.local_position);
+    } else if in.slot == 2u {
+        color = gradient_color(material.stroke, in.local_position);
+    }
+
+#ifdef TONEMAP_IN_SHADER
+    color = tonemapping::tone_mapping(color, view.color_grading);
+#endif
+    return color;
+}
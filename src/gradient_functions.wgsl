@@ -0,0 +1,79 @@
+#define_import_path bevy_prototype_lyon::gradient
+
+const MAX_STOPS: u32 = 8u;
+const TAU: f32 = 6.283185307179586;
+
+struct Gradient {
+    params: vec4<f32>,
+    colors: array<vec4<f32>, 8>,
+    offsets: array<vec4<f32>, 2>,
+    kind: u32,
+    spread: u32,
+    len: u32,
+};
+
+fn gradient_offset(gradient: Gradient, p: vec2<f32>) -> f32 {
+    switch gradient.kind {
+        case 0u: {
+            let start = gradient.params.xy;
+            let d = gradient.params.zw - start;
+            let len_sq = dot(d, d);
+            if len_sq == 0.0 {
+                return 0.0;
+            }
+            return dot(p - start, d) / len_sq;
+        }
+        case 1u: {
+            if gradient.params.z == 0.0 {
+                return 1.0;
+            }
+            return length(p - gradient.params.xy) / gradient.params.z;
+        }
+        default: {
+            let v = p - gradient.params.xy;
+            return fract((atan2(v.y, v.x) - gradient.params.z) / TAU);
+        }
+    }
+}
+
+fn apply_spread(spread: u32, t: f32) -> f32 {
+    switch spread {
+        case 1u: {
+            return fract(t);
+        }
+        case 2u: {
+            return 1.0 - abs(fract(t * 0.5) * 2.0 - 1.0);
+        }
+        default: {
+            return t;
+        }
+    }
+}
+
+fn stop_offset(gradient: Gradient, i: u32) -> f32 {
+    return gradient.offsets[i / 4u][i % 4u];
+}
+
+fn gradient_color(gradient: Gradient, p: vec2<f32>) -> vec4<f32> {
+    if gradient.len == 0u {
+        return vec4<f32>(0.0);
+    }
+    let t = apply_spread(gradient.spread, gradient_offset(gradient, p));
+
+    if t <= stop_offset(gradient, 0u) {
+        return gradient.colors[0];
+    }
+    for (var i = 1u; i < min(gradient.len, MAX_STOPS); i += 1u) {
+        let end = stop_offset(gradient, i);
+        if t <= end {
+            let start = stop_offset(gradient, i - 1u);
+            let span = end - start;
+            var k = 0.0;
+            if span > 0.0 {
+                k = (t - start) / span;
+            }
+            return mix(gradient.colors[i - 1u], gradient.colors[i], k);
+        }
+    }
+    return gradient.colors[gradient.len - 1u];
+}
@@ -0,0 +1,213 @@
+//! Point-in-shape hit testing.
+//!
+//! Points can be tested in the local space of a shape with the methods of
+//! [`Shape`], or in world space with the [`ShapeHitTest`] system parameter.
+
+use bevy::{ecs::system::SystemParam, prelude::*};
+use lyon_algorithms::hit_test::hit_test_path;
+use lyon_tessellation::{
+    geom::LineSegment,
+    math::Point,
+    path::{iterator::PathIterator, Path, PathEvent},
+};
+
+use crate::{bounds::ShapeBounds, entity::Shape, utils::ToPoint};
+
+impl Shape {
+    /// Returns `true` if `point`, in the local space of the shape,
+    /// is inside its fill, according to the fill rule.
+    ///
+    /// Always returns `false` if the shape has no fill.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bevy::{color::palettes::css::*, prelude::*};
+    /// use bevy_prototype_lyon::prelude::*;
+    ///
+    /// let circle = shapes::Circle {
+    ///     radius: 10.0,
+    ///     ..default()
+    /// };
+    /// let shape = ShapeBuilder::with(&circle).fill(RED).build();
+    ///
+    /// assert!(shape.fill_contains(Vec2::new(5.0, 5.0)));
+    /// assert!(!shape.fill_contains(Vec2::new(10.0, 10.0)));
+    /// ```
+    #[must_use]
+    pub fn fill_contains(&self, point: Vec2) -> bool {
+        self.fill.is_some_and(|fill| {
+            hit_test_path(
+                &point.to_point(),
+                self.path.iter(),
+                fill.options.fill_rule,
+                fill.options.tolerance,
+            )
+        })
+    }
+
+    /// Returns `true` if `point`, in the local space of the shape,
+    /// is within half the line width from the path.
+    ///
+    /// Joins and caps are considered round.
+    /// Only the trimmed portion of the path is tested.
+    /// Always returns `false` if the shape has no stroke.
+    #[must_use]
+    pub fn stroke_contains(&self, point: Vec2) -> bool {
+        self.stroke.is_some_and(|stroke| {
+            let half_width = stroke.options.line_width / 2.0;
+            distance_to_path(
+                &stroke.path(&self.path),
+                point.to_point(),
+                stroke.options.tolerance,
+            )
+            .is_some_and(|distance| distance <= half_width)
+        })
+    }
+
+    /// Returns `true` if `point`, in the local space of the shape,
+    /// is inside its fill or its stroke.
+    #[must_use]
+    pub fn contains(&self, point: Vec2) -> bool {
+        self.fill_contains(point) || self.stroke_contains(point)
+    }
+}
+
+/// Returns the distance from `point` to the nearest segment of the flattened
+/// `path`, or `None` if the path has no segments.
+fn distance_to_path(path: &Path, point: Point, tolerance: f32) -> Option<f32> {
+    path.iter()
+        .flattened(tolerance)
+        .filter_map(|event| match event {
+            PathEvent::Line { from, to } => Some(LineSegment { from, to }),
+            PathEvent::End {
+                last,
+                first,
+                close: true,
+            } => Some(LineSegment {
+                from: last,
+                to: first,
+            }),
+            _ => None,
+        })
+        .map(|segment| segment.square_distance_to_point(point))
+        .reduce(f32::min)
+        .map(f32::sqrt)
+}
+
+/// `SystemParam` to hit test shapes in world space,
+/// through their [`GlobalTransform`].
+///
+/// World points are tested at the depth of each shape,
+/// so shapes are expected to lie on the XY plane.
+///
+/// # Example
+///
+/// ```
+/// use bevy::{prelude::*, window::PrimaryWindow};
+/// use bevy_prototype_lyon::prelude::*;
+///
+/// fn hover_system(
+///     hit_test: ShapeHitTest,
+///     windows: Query<&Window, With<PrimaryWindow>>,
+///     cameras: Query<(&Camera, &GlobalTransform)>,
+/// ) {
+///     let (Ok(window), Ok((camera, camera_transform))) =
+///         (windows.get_single(), cameras.get_single())
+///     else {
+///         return;
+///     };
+///     let Some(point) = window
+///         .cursor_position()
+///         .and_then(|cursor| camera.viewport_to_world_2d(camera_transform, cursor).ok())
+///     else {
+///         return;
+///     };
+///     if let Some(&entity) = hit_test.hits(point).first() {
+///         info!("Hovering {entity}");
+///     }
+/// }
+/// ```
+#[derive(SystemParam)]
+pub struct ShapeHitTest<'w, 's> {
+    shapes: Query<
+        'w,
+        's,
+        (
+            Entity,
+            &'static Shape,
+            &'static GlobalTransform,
+            &'static ShapeBounds,
+        ),
+    >,
+}
+
+impl ShapeHitTest<'_, '_> {
+    /// Returns `true` if `point`, in world space,
+    /// is inside the fill of the shape `entity`.
+    ///
+    /// Returns `false` if `entity` is not a shape.
+    #[must_use]
+    pub fn fill_contains(&self, entity: Entity, point: Vec2) -> bool {
+        self.test(entity, point, Shape::fill_contains)
+    }
+
+    /// Returns `true` if `point`, in world space,
+    /// is inside the stroke of the shape `entity`.
+    ///
+    /// Returns `false` if `entity` is not a shape.
+    #[must_use]
+    pub fn stroke_contains(&self, entity: Entity, point: Vec2) -> bool {
+        self.test(entity, point, Shape::stroke_contains)
+    }
+
+    /// Returns `true` if `point`, in world space,
+    /// is inside the fill or the stroke of the shape `entity`.
+    ///
+    /// Returns `false` if `entity` is not a shape.
+    #[must_use]
+    pub fn contains(&self, entity: Entity, point: Vec2) -> bool {
+        self.test(entity, point, Shape::contains)
+    }
+
+    /// Returns the shapes containing `point`, in world space,
+    /// sorted from the topmost to the bottommost.
+    #[must_use]
+    pub fn hits(&self, point: Vec2) -> Vec<Entity> {
+        let mut hits: Vec<_> = self
+            .shapes
+            .iter()
+            .filter(|(_, shape, transform, bounds)| {
+                may_contain(bounds, point) && shape.contains(to_local(transform, point))
+            })
+            .map(|(entity, _, transform, _)| (entity, transform.translation().z))
+            .collect();
+        hits.sort_by(|(_, a), (_, b)| b.total_cmp(a));
+        hits.into_iter().map(|(entity, _)| entity).collect()
+    }
+
+    fn test(&self, entity: Entity, point: Vec2, test: impl Fn(&Shape, Vec2) -> bool) -> bool {
+        self.shapes
+            .get(entity)
+            .is_ok_and(|(_, shape, transform, bounds)| {
+                may_contain(bounds, point) && test(shape, to_local(transform, point))
+            })
+    }
+}
+
+/// Returns `false` if `point` is outside the world bounds of a shape.
+///
+/// Bounds that are not computed yet do not exclude any point.
+fn may_contain(bounds: &ShapeBounds, point: Vec2) -> bool {
+    let world = bounds.world();
+    world.is_empty() || world.contains(point)
+}
+
+/// Projects a world point into the local space of a shape.
+fn to_local(transform: &GlobalTransform, point: Vec2) -> Vec2 {
+    transform
+        .affine()
+        .inverse()
+        .transform_point3(point.extend(transform.translation().z))
+        .truncate()
+}
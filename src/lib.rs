@@ -23,29 +23,78 @@
 #![allow(clippy::forget_non_drop)]
 #![allow(clippy::missing_const_for_fn)]
 
+pub mod boolean;
+pub mod bounds;
+pub mod curve_fit;
+pub mod distance;
 pub mod draw;
 pub mod entity;
+pub mod extrude;
+pub mod fit;
+pub mod follow;
 pub mod geometry;
+pub mod gradient;
+pub mod hit_test;
+pub mod measure;
+pub mod morph;
+pub mod offset;
+pub mod overlap;
 pub mod path;
+#[cfg(feature = "picking")]
+pub mod picking;
 pub mod plugin;
+pub mod raycast;
+pub mod scatter;
+#[cfg(feature = "3d")]
+pub mod shape3d;
 pub mod shapes;
+pub mod simplify;
+pub mod transform;
+#[cfg(feature = "ui")]
+pub mod ui;
 
 mod utils;
 mod vertex;
 
+pub use vertex::{AttributeWriter, ShapeVertexAttributes, ShapeVertexConstructor};
+
 /// Import this module as `use bevy_prototype_lyon::prelude::*` to get
 /// convenient imports.
 pub mod prelude {
     pub use lyon_tessellation::{
-        self as tess, FillOptions, FillRule, LineCap, LineJoin, Orientation, StrokeOptions,
+        self as tess, path::Winding, FillOptions, FillRule, LineCap, LineJoin, Orientation,
+        StrokeOptions,
     };
 
+    #[cfg(feature = "picking")]
+    pub use crate::picking::{ShapeHits, ShapePart, ShapePickingPlugin};
+    #[cfg(feature = "3d")]
+    pub use crate::shape3d::{Billboard, Shape3d, ShapePlane};
+    #[cfg(feature = "ui")]
+    pub use crate::ui::ShapeNode;
     pub use crate::{
-        draw::{Fill, Stroke},
+        boolean::{BooleanOp, BooleanPath},
+        bounds::{BoundsMode, ShapeBounds},
+        curve_fit::CurveFitOptions,
+        distance::PathLocation,
+        draw::{Fill, Stroke, StrokeUvMapping, Trim, UvMapping},
         entity::Shape,
+        extrude::{Bevel, ExtrusionNormals, ExtrusionOptions},
+        fit::FitMode,
+        follow::{FollowMode, FollowPath, FollowTarget, ShapeMeasure},
         geometry::{Geometry, ShapeBuilder, ShapeBuilderBase},
+        gradient::{Gradient, GradientKind, GradientMaterial, SpreadMode},
+        hit_test::ShapeHitTest,
+        measure::{PathMeasure, PathSample},
+        morph::{PathMorph, ShapeMorph},
+        offset::OffsetOptions,
+        overlap::ShapeOverlaps,
         path::ShapePath,
-        plugin::ShapePlugin,
+        plugin::{ShapeMaterialPlugin, ShapePlugin},
+        raycast::{ShapeRayCast, ShapeRayCastSettings, ShapeRayHit},
+        scatter::{Scatter, ScatterPath},
         shapes::{self, BorderRadii, RectangleOrigin, RegularPolygon, RegularPolygonFeature},
+        transform::Transformed,
+        vertex::{AttributeWriter, ShapeVertexAttributes, ShapeVertexConstructor},
     };
 }
@@ -0,0 +1,259 @@
+//! Measurement of paths.
+//!
+//! A [`PathMeasure`] caches the lengths of the segments of a path,
+//! to sample points and tangents at given distances along it,
+//! or to extract the parts of the path between two distances.
+
+use std::ops::Range;
+
+use bevy::math::Vec2;
+use lyon_algorithms::{
+    measure::{PathMeasurements, SampleType},
+    walk::{walk_along_path, RegularPattern, WalkerEvent},
+};
+use lyon_tessellation::path::Path;
+
+use crate::{entity::Shape, path::ShapePath};
+
+/// A point sampled along a path.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PathSample {
+    /// Position of the point.
+    pub position: Vec2,
+    /// Unit tangent of the path at the point, in the drawing direction.
+    pub tangent: Vec2,
+    /// Length of the path from its beginning to the point.
+    pub distance: f32,
+}
+
+/// Measurements of a path, to sample it by distance.
+///
+/// Distances run along all subpaths in drawing order,
+/// including the lines closing the subpaths.
+///
+/// # Example
+///
+/// ```
+/// use bevy::{color::palettes::css::*, prelude::*};
+/// use bevy_prototype_lyon::prelude::*;
+///
+/// let road = ShapePath::new()
+///     .move_to(Vec2::ZERO)
+///     .line_to(Vec2::new(100.0, 0.0))
+///     .line_to(Vec2::new(100.0, 100.0));
+/// let measure = road.measure(0.1);
+/// assert_eq!(measure.length(), 200.0);
+///
+/// let halfway = measure.sample(100.0).unwrap();
+/// assert_eq!(halfway.position, Vec2::new(100.0, 0.0));
+///
+/// // Places a tree every 50 units, starting at the beginning of the road.
+/// let trees = measure.sample_evenly(5);
+/// assert_eq!(trees[1].position, Vec2::new(50.0, 0.0));
+/// assert_eq!(trees[1].tangent, Vec2::X);
+/// ```
+pub struct PathMeasure {
+    path: Path,
+    measurements: PathMeasurements,
+    tolerance: f32,
+}
+
+impl PathMeasure {
+    /// Measures `path`, flattening curves with `tolerance`.
+    #[must_use]
+    pub fn new(path: &Path, tolerance: f32) -> Self {
+        Self {
+            path: path.clone(),
+            measurements: PathMeasurements::from_path(path, tolerance),
+            tolerance,
+        }
+    }
+
+    /// Returns the length of the path.
+    #[must_use]
+    pub fn length(&self) -> f32 {
+        self.measurements.length()
+    }
+
+    /// Returns the point at `distance` from the beginning of the path,
+    /// or `None` if the path is empty.
+    ///
+    /// The distance is clamped between `0.0` and the length of the path.
+    #[must_use]
+    pub fn sample(&self, distance: f32) -> Option<PathSample> {
+        self.sample_all([distance]).pop()
+    }
+
+    /// Returns the point at `fraction` of the length of the path,
+    /// or `None` if the path is empty.
+    ///
+    /// The fraction is clamped between `0.0` and `1.0`.
+    #[must_use]
+    pub fn sample_fraction(&self, fraction: f32) -> Option<PathSample> {
+        self.sample(fraction.clamp(0.0, 1.0) * self.length())
+    }
+
+    /// Returns `count` points evenly spaced along the path,
+    /// including its beginning and its end.
+    ///
+    /// Returns no points if the path is empty.
+    #[must_use]
+    pub fn sample_evenly(&self, count: usize) -> Vec<PathSample> {
+        let step = self.length() / count.saturating_sub(1).max(1) as f32;
+        self.sample_all((0..count).map(|i| i as f32 * step))
+    }
+
+    /// Walks along the path from `start`,
+    /// calling `callback` every `interval` units of length,
+    /// until it returns `false` or the path ends.
+    ///
+    /// Does nothing if `interval` is not positive.
+    pub fn walk(&self, start: f32, interval: f32, mut callback: impl FnMut(PathSample) -> bool) {
+        if interval <= 0.0 {
+            return;
+        }
+        let mut pattern = RegularPattern {
+            callback: |event: WalkerEvent| {
+                callback(PathSample {
+                    position: Vec2::new(event.position.x, event.position.y),
+                    tangent: Vec2::new(event.tangent.x, event.tangent.y).normalize_or_zero(),
+                    distance: event.distance,
+                })
+            },
+            interval,
+        };
+        walk_along_path(self.path.iter(), start, self.tolerance, &mut pattern);
+    }
+
+    /// Returns the part of the path between the distances of `range`.
+    ///
+    /// Distances are clamped between `0.0` and the length of the path.
+    /// The returned path is empty if the range is.
+    #[must_use]
+    pub fn slice(&self, range: Range<f32>) -> Path {
+        let mut builder = Path::builder();
+        if self.path.iter().next().is_some() {
+            self.measurements
+                .create_sampler(&self.path, SampleType::Distance)
+                .split_range(range, &mut builder);
+        }
+        builder.build()
+    }
+
+    /// Samples the path at each distance.
+    fn sample_all(&self, distances: impl IntoIterator<Item = f32>) -> Vec<PathSample> {
+        if self.path.iter().next().is_none() {
+            return Vec::new();
+        }
+        let length = self.length();
+        let mut sampler = self
+            .measurements
+            .create_sampler(&self.path, SampleType::Distance);
+        distances
+            .into_iter()
+            .map(|distance| {
+                let distance = distance.clamp(0.0, length);
+                let sample = sampler.sample(distance);
+                let (position, tangent) = (sample.position(), sample.tangent());
+                PathSample {
+                    position: Vec2::new(position.x, position.y),
+                    tangent: Vec2::new(tangent.x, tangent.y).normalize_or_zero(),
+                    distance,
+                }
+            })
+            .collect()
+    }
+}
+
+impl Shape {
+    /// Measures the path,
+    /// flattening curves with the tolerance of the fill, or of the stroke.
+    #[must_use]
+    pub fn measure(&self) -> PathMeasure {
+        PathMeasure::new(&self.path, self.tolerance())
+    }
+}
+
+impl ShapePath {
+    /// Measures the path, flattening curves with `tolerance`.
+    #[must_use]
+    pub fn measure(&self, tolerance: f32) -> PathMeasure {
+        PathMeasure::new(&self.build(), tolerance)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EPSILON: f32 = 1e-3;
+
+    /// An L-shaped polyline of length 200.
+    fn corner() -> PathMeasure {
+        ShapePath::new()
+            .move_to(Vec2::ZERO)
+            .line_to(Vec2::new(100.0, 0.0))
+            .line_to(Vec2::new(100.0, 100.0))
+            .measure(0.1)
+    }
+
+    fn slice_length(measure: &PathMeasure, range: Range<f32>) -> f32 {
+        PathMeasure::new(&measure.slice(range), 0.1).length()
+    }
+
+    #[test]
+    fn slice_has_the_length_of_its_range() {
+        let measure = corner();
+        assert!((slice_length(&measure, 50.0..150.0) - 100.0).abs() < EPSILON);
+        assert!((slice_length(&measure, 0.0..200.0) - 200.0).abs() < EPSILON);
+        // The range is clamped to the path.
+        assert!((slice_length(&measure, -50.0..50.0) - 50.0).abs() < EPSILON);
+        assert!((slice_length(&measure, 150.0..300.0) - 50.0).abs() < EPSILON);
+    }
+
+    #[test]
+    fn slice_of_empty_range_is_empty() {
+        let measure = corner();
+        assert!(measure.slice(80.0..80.0).iter().next().is_none());
+        assert!(ShapePath::new()
+            .measure(0.1)
+            .slice(0.0..10.0)
+            .iter()
+            .next()
+            .is_none());
+    }
+
+    #[test]
+    fn sample_evenly_spaces_the_samples() {
+        let measure = corner();
+        let samples = measure.sample_evenly(5);
+        let distances: Vec<_> = samples.iter().map(|sample| sample.distance).collect();
+        assert_eq!(distances, [0.0, 50.0, 100.0, 150.0, 200.0]);
+        assert!(samples[4].position.distance(Vec2::splat(100.0)) < EPSILON);
+        assert!(samples[3].tangent.distance(Vec2::Y) < EPSILON);
+    }
+
+    #[test]
+    fn sample_evenly_handles_small_counts() {
+        let measure = corner();
+        assert!(measure.sample_evenly(0).is_empty());
+        let single = measure.sample_evenly(1);
+        assert_eq!(single.len(), 1);
+        assert!(single[0].distance.abs() <= f32::EPSILON);
+        assert!(ShapePath::new().measure(0.1).sample_evenly(3).is_empty());
+    }
+
+    #[test]
+    fn closing_line_is_measured() {
+        let square = ShapePath::new()
+            .move_to(Vec2::ZERO)
+            .line_to(Vec2::new(10.0, 0.0))
+            .line_to(Vec2::new(10.0, 10.0))
+            .line_to(Vec2::new(0.0, 10.0))
+            .close()
+            .measure(0.1);
+        assert!((square.length() - 40.0).abs() < EPSILON);
+        let sample = square.sample(35.0).unwrap();
+        assert!(sample.position.distance(Vec2::new(0.0, 5.0)) < EPSILON);
+    }
+}
@@ -0,0 +1,471 @@
+//! Morphing between paths.
+//!
+//! A [`PathMorph`] pairs the subpaths of two paths
+//! and gives them the same number of segments,
+//! so intermediate paths can be interpolated for any factor.
+//! The [`ShapeMorph`] component applies a morph to the path of a shape,
+//! according to its `progress`,
+//! which can be driven by Bevy's animation system.
+
+use std::sync::Arc;
+
+use bevy::prelude::*;
+use lyon_tessellation::{
+    geom::CubicBezierSegment,
+    math::Point,
+    path::{Path, PathEvent},
+};
+
+use crate::entity::Shape;
+
+/// Minimum number of curves of paired subpaths,
+/// so their starting points can be aligned finely.
+const MIN_CURVES: usize = 32;
+
+/// A subpath, as a sequence of cubic bézier curves.
+#[derive(Debug, Clone, Default)]
+struct Contour {
+    curves: Vec<CubicBezierSegment<f32>>,
+    closed: bool,
+}
+
+impl Contour {
+    /// Returns the mean of the endpoints of the curves.
+    fn center(&self) -> Point {
+        let sum = self
+            .curves
+            .iter()
+            .fold(Point::zero().to_vector(), |sum, curve| {
+                sum + curve.from.to_vector()
+            });
+        (sum / self.curves.len().max(1) as f32).to_point()
+    }
+
+    /// Returns the signed area enclosed by the control polygons.
+    fn area(&self) -> f32 {
+        self.curves
+            .iter()
+            .flat_map(|curve| {
+                [
+                    (curve.from, curve.ctrl1),
+                    (curve.ctrl1, curve.ctrl2),
+                    (curve.ctrl2, curve.to),
+                ]
+            })
+            .map(|(a, b)| a.to_vector().cross(b.to_vector()))
+            .sum::<f32>()
+            / 2.0
+    }
+
+    /// Returns a contour with `count` curves collapsed into `point`.
+    fn collapsed(point: Point, count: usize, closed: bool) -> Self {
+        let curve = CubicBezierSegment {
+            from: point,
+            ctrl1: point,
+            ctrl2: point,
+            to: point,
+        };
+        Self {
+            curves: vec![curve; count],
+            closed,
+        }
+    }
+
+    fn reverse(&mut self) {
+        self.curves.reverse();
+        for curve in &mut self.curves {
+            *curve = CubicBezierSegment {
+                from: curve.to,
+                ctrl1: curve.ctrl2,
+                ctrl2: curve.ctrl1,
+                to: curve.from,
+            };
+        }
+    }
+
+    /// Splits the longest curves in halves until there are `count` curves.
+    fn subdivide(&mut self, count: usize) {
+        while self.curves.len() < count {
+            let Some((index, _)) = self
+                .curves
+                .iter()
+                .enumerate()
+                .max_by(|(_, a), (_, b)| chord(a).total_cmp(&chord(b)))
+            else {
+                return;
+            };
+            let (first, second) = self.curves[index].split(0.5);
+            self.curves[index] = first;
+            self.curves.insert(index + 1, second);
+        }
+    }
+
+    /// Returns the sum of the squared distances between the corresponding
+    /// control points of two contours with the same number of curves,
+    /// the second one starting from its curve at `offset`.
+    fn distance(&self, other: &Self, offset: usize) -> f32 {
+        let n = other.curves.len();
+        self.curves
+            .iter()
+            .enumerate()
+            .map(|(i, a)| {
+                let b = &other.curves[(i + offset) % n];
+                (a.from - b.from).square_length()
+                    + (a.ctrl1 - b.ctrl1).square_length()
+                    + (a.ctrl2 - b.ctrl2).square_length()
+            })
+            .sum()
+    }
+}
+
+/// Returns the length of the control polygon of a curve.
+fn chord(curve: &CubicBezierSegment<f32>) -> f32 {
+    (curve.ctrl1 - curve.from).length()
+        + (curve.ctrl2 - curve.ctrl1).length()
+        + (curve.to - curve.ctrl2).length()
+}
+
+/// Interpolation between two paths.
+///
+/// Subpaths are paired in drawing order.
+/// Subpaths without a counterpart grow from, or shrink into,
+/// their center.
+/// Closed subpaths are given the same winding,
+/// and the starting points that minimize the movement.
+///
+/// # Example
+///
+/// ```
+/// use bevy::{color::palettes::css::*, prelude::*};
+/// use bevy_prototype_lyon::prelude::*;
+///
+/// let square = ShapeBuilder::with(&shapes::Rectangle {
+///     extents: Vec2::splat(100.0),
+///     ..default()
+/// })
+/// .fill(RED)
+/// .build();
+/// let star = ShapeBuilder::with(&shapes::RegularPolygon {
+///     sides: 10,
+///     feature: RegularPolygonFeature::Radius(60.0),
+///     ..default()
+/// })
+/// .fill(RED)
+/// .build();
+///
+/// let morph = PathMorph::new(&square.path, &star.path);
+/// let halfway = morph.sample(0.5);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct PathMorph {
+    pairs: Vec<(Contour, Contour)>,
+}
+
+impl PathMorph {
+    /// Prepares the interpolation from the path `from` to the path `to`.
+    #[must_use]
+    pub fn new(from: &Path, to: &Path) -> Self {
+        let from = contours(from);
+        let to = contours(to);
+        let pairs = (0..from.len().max(to.len()))
+            .map(|i| match (from.get(i), to.get(i)) {
+                (Some(a), Some(b)) => pair(a.clone(), b.clone()),
+                (Some(a), None) => {
+                    let b = Contour::collapsed(a.center(), a.curves.len(), a.closed);
+                    (a.clone(), b)
+                }
+                (None, Some(b)) => {
+                    let a = Contour::collapsed(b.center(), b.curves.len(), b.closed);
+                    (a, b.clone())
+                }
+                (None, None) => unreachable!(),
+            })
+            .collect();
+        Self { pairs }
+    }
+
+    /// Returns the path interpolated at `t`,
+    /// from the first path at `0.0` to the second one at `1.0`.
+    ///
+    /// Values outside of that range extrapolate.
+    #[must_use]
+    pub fn sample(&self, t: f32) -> Path {
+        let mut builder = Path::builder();
+        for (a, b) in &self.pairs {
+            let mut curves = a
+                .curves
+                .iter()
+                .zip(&b.curves)
+                .map(|(a, b)| CubicBezierSegment {
+                    from: a.from.lerp(b.from, t),
+                    ctrl1: a.ctrl1.lerp(b.ctrl1, t),
+                    ctrl2: a.ctrl2.lerp(b.ctrl2, t),
+                    to: a.to.lerp(b.to, t),
+                });
+            let Some(first) = curves.next() else {
+                continue;
+            };
+            builder.begin(first.from);
+            for curve in std::iter::once(first).chain(curves) {
+                builder.cubic_bezier_to(curve.ctrl1, curve.ctrl2, curve.to);
+            }
+            builder.end(a.closed && b.closed);
+        }
+        builder.build()
+    }
+}
+
+/// Gives the same number of curves, winding and start to two contours.
+fn pair(mut a: Contour, mut b: Contour) -> (Contour, Contour) {
+    let count = a.curves.len().max(b.curves.len()).max(MIN_CURVES);
+    a.subdivide(count);
+    b.subdivide(count);
+    if a.closed && b.closed {
+        if a.area() * b.area() < 0.0 {
+            b.reverse();
+        }
+        let offset = (0..count)
+            .min_by(|i, j| a.distance(&b, *i).total_cmp(&a.distance(&b, *j)))
+            .unwrap_or(0);
+        b.curves.rotate_left(offset);
+    } else {
+        let mut reversed = b.clone();
+        reversed.reverse();
+        if a.distance(&reversed, 0) < a.distance(&b, 0) {
+            b = reversed;
+        }
+    }
+    (a, b)
+}
+
+/// Converts the subpaths of `path` into contours.
+///
+/// Closed subpaths include their closing line.
+fn contours(path: &Path) -> Vec<Contour> {
+    let line = |from: Point, to: Point| CubicBezierSegment {
+        from,
+        ctrl1: from.lerp(to, 1.0 / 3.0),
+        ctrl2: from.lerp(to, 2.0 / 3.0),
+        to,
+    };
+    let mut contours = Vec::new();
+    let mut current = Contour::default();
+    for event in path {
+        match event {
+            PathEvent::Begin { .. } => current = Contour::default(),
+            PathEvent::Line { from, to } => current.curves.push(line(from, to)),
+            PathEvent::Quadratic { from, ctrl, to } => current.curves.push(
+                lyon_tessellation::geom::QuadraticBezierSegment { from, ctrl, to }.to_cubic(),
+            ),
+            PathEvent::Cubic {
+                from,
+                ctrl1,
+                ctrl2,
+                to,
+            } => current.curves.push(CubicBezierSegment {
+                from,
+                ctrl1,
+                ctrl2,
+                to,
+            }),
+            PathEvent::End { last, first, close } => {
+                if close && last != first {
+                    current.curves.push(line(last, first));
+                }
+                current.closed = close;
+                if !current.curves.is_empty() {
+                    contours.push(std::mem::take(&mut current));
+                }
+            }
+        }
+    }
+    contours
+}
+
+/// `Component` setting the path of a [`Shape`] to a [`PathMorph`],
+/// sampled at `progress`.
+///
+/// The path is updated whenever the component changes,
+/// so `progress` can be animated by any system,
+/// or by an `AnimationClip`, through the `animated_field!` macro.
+///
+/// # Example
+///
+/// ```
+/// use bevy::{
+///     animation::{animated_field, AnimationTarget, AnimationTargetId},
+///     color::palettes::css::*,
+///     prelude::*,
+/// };
+/// use bevy_prototype_lyon::prelude::*;
+///
+/// fn setup_system(
+///     mut commands: Commands,
+///     mut clips: ResMut<Assets<AnimationClip>>,
+///     mut graphs: ResMut<Assets<AnimationGraph>>,
+/// ) {
+///     let circle = ShapeBuilder::with(&shapes::Circle::default())
+///         .fill(RED)
+///         .build();
+///     let square = ShapeBuilder::with(&shapes::Rectangle::default())
+///         .fill(RED)
+///         .build();
+///     let morph = PathMorph::new(&circle.path, &square.path);
+///
+///     let name = Name::new("morphing");
+///     let target = AnimationTargetId::from_name(&name);
+///     let mut clip = AnimationClip::default();
+///     clip.add_curve_to_target(
+///         target,
+///         AnimatableCurve::new(
+///             animated_field!(ShapeMorph::progress),
+///             AnimatableKeyframeCurve::new([(0.0, 0.0), (1.0, 1.0)]).unwrap(),
+///         ),
+///     );
+///     let (graph, node) = AnimationGraph::from_clip(clips.add(clip));
+///     let mut player = AnimationPlayer::default();
+///     player.play(node).repeat();
+///
+///     let entity = commands
+///         .spawn((
+///             circle,
+///             ShapeMorph::new(morph),
+///             name,
+///             AnimationGraphHandle(graphs.add(graph)),
+///             player,
+///         ))
+///         .id();
+///     commands.entity(entity).insert(AnimationTarget {
+///         id: target,
+///         player: entity,
+///     });
+/// }
+/// ```
+#[derive(Component, Reflect, Clone, Default)]
+#[reflect(Component)]
+pub struct ShapeMorph {
+    /// Interpolation factor between the two paths of the morph.
+    pub progress: f32,
+    #[reflect(ignore)]
+    morph: Arc<PathMorph>,
+}
+
+impl ShapeMorph {
+    /// Creates the component at the beginning of `morph`.
+    #[must_use]
+    pub fn new(morph: PathMorph) -> Self {
+        Self {
+            progress: 0.0,
+            morph: Arc::new(morph),
+        }
+    }
+
+    /// Sets the progress of the morph.
+    #[must_use]
+    pub fn with_progress(mut self, progress: f32) -> Self {
+        self.progress = progress;
+        self
+    }
+
+    /// Returns the morph applied to the shape.
+    #[must_use]
+    pub fn morph(&self) -> &PathMorph {
+        &self.morph
+    }
+}
+
+/// Samples the morphs of changed [`ShapeMorph`]s into their [`Shape`]s.
+pub(crate) fn shape_morph_system(mut query: Query<(&ShapeMorph, &mut Shape), Changed<ShapeMorph>>) {
+    for (morph, mut shape) in &mut query {
+        shape.path = morph.morph.sample(morph.progress);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use lyon_algorithms::{aabb::bounding_box, area::approximate_signed_area};
+    use lyon_tessellation::math::point;
+
+    use super::*;
+
+    const EPSILON: f32 = 1e-3;
+
+    fn polygon(points: &[(f32, f32)], close: bool) -> Path {
+        let mut builder = Path::builder();
+        builder.begin(point(points[0].0, points[0].1));
+        for &(x, y) in &points[1..] {
+            builder.line_to(point(x, y));
+        }
+        builder.end(close);
+        builder.build()
+    }
+
+    fn square() -> Path {
+        polygon(&[(0.0, 0.0), (10.0, 0.0), (10.0, 10.0), (0.0, 10.0)], true)
+    }
+
+    /// A clockwise triangle.
+    fn triangle() -> Path {
+        polygon(&[(20.0, 0.0), (25.0, 10.0), (30.0, 0.0)], true)
+    }
+
+    fn assert_same_outline(a: &Path, b: &Path) {
+        let area = |path: &Path| approximate_signed_area(0.01, path.iter()).abs();
+        assert!((area(a) - area(b)).abs() < EPSILON);
+        let (box_a, box_b) = (bounding_box(a.iter()), bounding_box(b.iter()));
+        assert!((box_a.min - box_b.min).length() < EPSILON);
+        assert!((box_a.max - box_b.max).length() < EPSILON);
+    }
+
+    fn endpoints(path: &Path) -> Vec<Point> {
+        path.iter()
+            .filter_map(|event| match event {
+                PathEvent::Begin { at } => Some(at),
+                PathEvent::End { last, .. } => Some(last),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn closed_morph_matches_its_ends() {
+        let morph = PathMorph::new(&square(), &triangle());
+        assert_same_outline(&morph.sample(0.0), &square());
+        assert_same_outline(&morph.sample(1.0), &triangle());
+    }
+
+    #[test]
+    fn closed_morph_keeps_the_winding() {
+        let morph = PathMorph::new(&square(), &triangle());
+        let area = |path: &Path| approximate_signed_area(0.01, path.iter());
+        let square_area = area(&square());
+        assert!(area(&morph.sample(1.0)) * square_area > 0.0);
+        assert!(area(&morph.sample(0.5)) * square_area > 0.0);
+    }
+
+    #[test]
+    fn open_morph_matches_its_ends() {
+        let from = polygon(&[(0.0, 0.0), (10.0, 0.0)], false);
+        let to = polygon(&[(10.0, 10.0), (0.0, 10.0)], false);
+        let morph = PathMorph::new(&from, &to);
+        assert_eq!(endpoints(&morph.sample(0.0)), endpoints(&from));
+        // The target is reversed, so its nearest end is paired with the start.
+        let end = endpoints(&morph.sample(1.0));
+        assert!((end[0] - point(0.0, 10.0)).length() < EPSILON);
+        assert!((end[1] - point(10.0, 10.0)).length() < EPSILON);
+    }
+
+    #[test]
+    fn unpaired_subpath_shrinks_into_its_center() {
+        let mut builder = Path::builder();
+        builder.extend_from_paths(&[square().as_slice(), triangle().as_slice()]);
+        let from = builder.build();
+        let morph = PathMorph::new(&from, &square());
+        assert_same_outline(&morph.sample(0.0), &from);
+        let end = morph.sample(1.0);
+        let area = approximate_signed_area(0.01, end.iter());
+        assert!((area - approximate_signed_area(0.01, square().iter())).abs() < EPSILON);
+        let collapsed = endpoints(&end)[2];
+        assert!((collapsed - point(25.0, 10.0 / 3.0)).length() < EPSILON);
+    }
+}
@@ -0,0 +1,522 @@
+//! Offsetting of paths.
+//!
+//! Closed subpaths are offset as areas: outwards for positive distances,
+//! inwards for negative ones.
+//! Open subpaths are offset on both sides into a band,
+//! whose ends are shaped by a [`LineCap`].
+//!
+//! The result is a [`BooleanPath`] made of straight lines,
+//! free of the self-intersections that offsetting may introduce.
+
+use std::f64::consts::TAU;
+
+use bevy::math::DVec2;
+use lyon_tessellation::{
+    math::Point,
+    path::{iterator::PathIterator, Path, PathEvent},
+    FillOptions, FillRule, LineCap, LineJoin, StrokeOptions,
+};
+
+use crate::{
+    boolean::{overlay, BooleanOp, BooleanPath, Operand},
+    entity::Shape,
+    path::ShapePath,
+};
+
+/// Parameters of path offsetting.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OffsetOptions {
+    /// Shape of the corners of the offset path.
+    ///
+    /// Default value: [`LineJoin::Miter`].
+    pub line_join: LineJoin,
+    /// Shape of the ends of offset open subpaths.
+    ///
+    /// Default value: [`LineCap::Butt`].
+    pub line_cap: LineCap,
+    /// Limit of the ratio between the length of a miter and the offset
+    /// distance, over which miters are beveled, or clipped with
+    /// [`LineJoin::MiterClip`].
+    ///
+    /// Default value: `4.0`.
+    pub miter_limit: f32,
+    /// Maximum distance between curves and the lines approximating them.
+    ///
+    /// Default value: `0.1`.
+    pub tolerance: f32,
+}
+
+impl Default for OffsetOptions {
+    fn default() -> Self {
+        Self {
+            line_join: StrokeOptions::DEFAULT_LINE_JOIN,
+            line_cap: StrokeOptions::DEFAULT_LINE_CAP,
+            miter_limit: StrokeOptions::DEFAULT_MITER_LIMIT,
+            tolerance: StrokeOptions::DEFAULT_TOLERANCE,
+        }
+    }
+}
+
+impl OffsetOptions {
+    /// Sets the shape of the corners.
+    #[must_use]
+    pub fn with_line_join(mut self, line_join: LineJoin) -> Self {
+        self.line_join = line_join;
+        self
+    }
+
+    /// Sets the shape of the ends of open subpaths.
+    #[must_use]
+    pub fn with_line_cap(mut self, line_cap: LineCap) -> Self {
+        self.line_cap = line_cap;
+        self
+    }
+
+    /// Sets the miter limit.
+    #[must_use]
+    pub fn with_miter_limit(mut self, miter_limit: f32) -> Self {
+        self.miter_limit = miter_limit;
+        self
+    }
+
+    /// Sets the tolerance.
+    #[must_use]
+    pub fn with_tolerance(mut self, tolerance: f32) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+}
+
+impl Shape {
+    /// Offsets the path by `distance`.
+    ///
+    /// The areas of closed subpaths are determined by the fill rule.
+    /// Shapes without fill use the default fill rule of [`FillOptions`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bevy::{color::palettes::css::*, prelude::*};
+    /// use bevy_prototype_lyon::prelude::*;
+    ///
+    /// let territory = ShapeBuilder::with(&shapes::RegularPolygon {
+    ///     sides: 5,
+    ///     feature: RegularPolygonFeature::Radius(100.0),
+    ///     ..default()
+    /// })
+    /// .fill(DARK_GREEN)
+    /// .build();
+    ///
+    /// let border = territory.offset(
+    ///     20.0,
+    ///     &OffsetOptions::default().with_line_join(LineJoin::Round),
+    /// );
+    /// let shape = ShapeBuilder::with(&border)
+    ///     .fill(GREEN.with_alpha(0.3))
+    ///     .build();
+    /// ```
+    #[must_use]
+    pub fn offset(&self, distance: f32, options: &OffsetOptions) -> BooleanPath {
+        let fill_rule = self.fill.map_or(FillOptions::DEFAULT_FILL_RULE, |fill| {
+            fill.options.fill_rule
+        });
+        BooleanPath::from_overlay(offset_path(&self.path, fill_rule, distance, options))
+    }
+}
+
+impl ShapePath {
+    /// Offsets the path by `distance`.
+    ///
+    /// The areas of closed subpaths are determined by `fill_rule`.
+    #[must_use]
+    pub fn offset(
+        &self,
+        distance: f32,
+        fill_rule: FillRule,
+        options: &OffsetOptions,
+    ) -> BooleanPath {
+        BooleanPath::from_overlay(offset_path(&self.build(), fill_rule, distance, options))
+    }
+}
+
+/// Offsets `path` by `distance`.
+///
+/// The areas of closed subpaths are grown or shrunk by the band covered by
+/// their outlines, while the bands of open subpaths are added to the
+/// result.
+/// Bands are the union of a rectangle per segment, a piece per join and a
+/// piece per cap.
+/// Their edges are all overlaid at once, in quadratic time (see [`overlay`]),
+/// so offsetting long or finely flattened paths is slow.
+fn offset_path(path: &Path, fill_rule: FillRule, distance: f32, options: &OffsetOptions) -> Path {
+    let tolerance = f64::from(options.tolerance);
+    let width = f64::from(distance.abs());
+    let polylines = flatten(path, options.tolerance);
+
+    let mut closed = Vec::new();
+    let mut closed_band = Vec::new();
+    let mut open_band = Vec::new();
+    for (points, close) in &polylines {
+        if *close && points.len() > 2 {
+            closed.push(points.clone());
+            band(points, true, width, options, &mut closed_band);
+        } else {
+            band(points, false, width, options, &mut open_band);
+        }
+    }
+
+    let op = if distance >= 0.0 {
+        BooleanOp::Union
+    } else {
+        BooleanOp::Difference
+    };
+    let areas = overlay(
+        &Operand::from_polygons(&closed, fill_rule),
+        op,
+        &Operand::from_polygons(&closed_band, FillRule::NonZero),
+        tolerance,
+    );
+    if open_band.is_empty() {
+        return areas;
+    }
+    overlay(
+        &Operand::new(&areas, FillRule::NonZero, options.tolerance),
+        BooleanOp::Union,
+        &Operand::from_polygons(&open_band, FillRule::NonZero),
+        tolerance,
+    )
+}
+
+/// Flattens the subpaths of `path` into polylines,
+/// along with a flag telling whether they are closed.
+fn flatten(path: &Path, tolerance: f32) -> Vec<(Vec<DVec2>, bool)> {
+    let to_dvec2 = |p: Point| DVec2::new(f64::from(p.x), f64::from(p.y));
+    let mut polylines = Vec::new();
+    let mut current: Vec<DVec2> = Vec::new();
+    for event in path.iter().flattened(tolerance) {
+        match event {
+            PathEvent::Begin { at } => current = vec![to_dvec2(at)],
+            PathEvent::Line { to, .. } => {
+                let to = to_dvec2(to);
+                if current.last() != Some(&to) {
+                    current.push(to);
+                }
+            }
+            PathEvent::End { close, .. } => {
+                if close && current.len() > 1 && current.first() == current.last() {
+                    current.pop();
+                }
+                polylines.push((std::mem::take(&mut current), close));
+            }
+            _ => {}
+        }
+    }
+    polylines
+}
+
+/// Pushes to `pieces` the polygons covering the band of half width `width`
+/// around a polyline.
+fn band(
+    points: &[DVec2],
+    closed: bool,
+    width: f64,
+    options: &OffsetOptions,
+    pieces: &mut Vec<Vec<DVec2>>,
+) {
+    if width <= 0.0 || points.is_empty() {
+        return;
+    }
+    let segment_count = if closed {
+        points.len()
+    } else {
+        points.len() - 1
+    };
+    let segments: Vec<_> = (0..segment_count)
+        .map(|i| (points[i], points[(i + 1) % points.len()]))
+        .collect();
+    let direction = |(from, to): (DVec2, DVec2)| (to - from).normalize();
+
+    let (Some(&first), Some(&last)) = (segments.first(), segments.last()) else {
+        // A lone point only has its caps.
+        pieces.extend(cap(points[0], DVec2::X, width, options));
+        pieces.extend(cap(points[0], DVec2::NEG_X, width, options));
+        return;
+    };
+    for &(from, to) in &segments {
+        let normal = direction((from, to)).perp() * width;
+        pieces.push(vec![from - normal, to - normal, to + normal, from + normal]);
+    }
+    let joins = usize::from(!closed)..segments.len();
+    for i in joins {
+        let incoming = segments[(i + segments.len() - 1) % segments.len()];
+        let outgoing = segments[i];
+        let (a, b) = (direction(incoming), direction(outgoing));
+        pieces.extend(join(outgoing.0, a, b, width, options));
+    }
+    if !closed {
+        pieces.extend(cap(first.0, -direction(first), width, options));
+        pieces.extend(cap(last.1, direction(last), width, options));
+    }
+}
+
+/// Returns the polygon filling the outer side of the corner at `vertex`,
+/// between the segments with direction `a` and `b`.
+fn join(
+    vertex: DVec2,
+    a: DVec2,
+    b: DVec2,
+    width: f64,
+    options: &OffsetOptions,
+) -> Option<Vec<DVec2>> {
+    let cross = a.perp_dot(b);
+    if cross.abs() <= f64::EPSILON && a.dot(b) > 0.0 {
+        return None;
+    }
+    // The outer side is on the right of left turns.
+    let side = if cross >= 0.0 { -width } else { width };
+    let (normal_a, normal_b) = (a.perp() * side, b.perp() * side);
+    let (start, end) = (vertex + normal_a, vertex + normal_b);
+    let bevel = vec![vertex, start, end];
+    let bisector = (normal_a + normal_b).normalize_or_zero();
+    let polygon = match options.line_join {
+        LineJoin::Round => {
+            let sweep = normal_a.perp_dot(normal_b).atan2(normal_a.dot(normal_b));
+            let mut polygon = vec![vertex];
+            polygon.extend(arc(vertex, normal_a, sweep, f64::from(options.tolerance)));
+            polygon
+        }
+        LineJoin::Bevel => bevel,
+        LineJoin::Miter | LineJoin::MiterClip if bisector == DVec2::ZERO => bevel,
+        LineJoin::Miter | LineJoin::MiterClip => {
+            // Ratio between the length of the miter and the width.
+            let ratio = width / bisector.dot(normal_a);
+            let limit = f64::from(options.miter_limit);
+            if ratio <= limit {
+                vec![vertex, start, vertex + bisector * width * ratio, end]
+            } else if options.line_join == LineJoin::MiterClip {
+                let clip = limit * width;
+                let along_a = (clip - (start - vertex).dot(bisector)) / a.dot(bisector);
+                let along_b = (clip - (end - vertex).dot(bisector)) / -b.dot(bisector);
+                vec![vertex, start, start + a * along_a, end - b * along_b, end]
+            } else {
+                bevel
+            }
+        }
+    };
+    Some(counter_clockwise(polygon))
+}
+
+/// Returns the polygon extending the end of a polyline at `point`,
+/// where it has `direction`.
+fn cap(point: DVec2, direction: DVec2, width: f64, options: &OffsetOptions) -> Option<Vec<DVec2>> {
+    let normal = direction.perp() * width;
+    let polygon = match options.line_cap {
+        LineCap::Butt => return None,
+        LineCap::Square => vec![
+            point - normal,
+            point - normal + direction * width,
+            point + normal + direction * width,
+            point + normal,
+        ],
+        LineCap::Round => {
+            let mut polygon = vec![point];
+            polygon.extend(arc(
+                point,
+                -normal,
+                std::f64::consts::PI,
+                f64::from(options.tolerance),
+            ));
+            polygon
+        }
+    };
+    Some(counter_clockwise(polygon))
+}
+
+/// Returns the points of an arc around `center`,
+/// starting at `center + radius` and sweeping by `sweep` radians.
+#[allow(clippy::cast_sign_loss)] // the number of points is positive
+fn arc(center: DVec2, radius: DVec2, sweep: f64, tolerance: f64) -> impl Iterator<Item = DVec2> {
+    let length = radius.length();
+    // Largest angle whose chord stays within the tolerance from the arc.
+    let step = if tolerance < length {
+        2.0 * (1.0 - tolerance / length).acos()
+    } else {
+        TAU / 4.0
+    };
+    let count = (sweep.abs() / step).ceil().max(1.0) as u32;
+    (0..=count).map(move |i| {
+        let angle = sweep * f64::from(i) / f64::from(count);
+        center + DVec2::from_angle(angle).rotate(radius)
+    })
+}
+
+/// Reverses `polygon` if it winds clockwise,
+/// so overlapping pieces add up with the non-zero fill rule.
+fn counter_clockwise(mut polygon: Vec<DVec2>) -> Vec<DVec2> {
+    let area: f64 = polygon
+        .iter()
+        .zip(polygon.iter().cycle().skip(1))
+        .map(|(a, b)| a.perp_dot(*b))
+        .sum();
+    if area < 0.0 {
+        polygon.reverse();
+    }
+    polygon
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::PI;
+
+    use bevy::math::Vec2;
+    use lyon_algorithms::{aabb::bounding_box, area::approximate_signed_area};
+
+    use super::*;
+
+    fn polygon(points: &[(f32, f32)], close: bool) -> ShapePath {
+        let mut path = ShapePath::new().move_to(Vec2::from(points[0]));
+        for point in &points[1..] {
+            path = path.line_to(Vec2::from(*point));
+        }
+        if close {
+            path = path.close();
+        }
+        path
+    }
+
+    fn square(size: f32) -> ShapePath {
+        polygon(&[(0.0, 0.0), (size, 0.0), (size, size), (0.0, size)], true)
+    }
+
+    fn offset(path: &ShapePath, distance: f32, options: OffsetOptions) -> Path {
+        path.offset(distance, FillRule::NonZero, &options.with_tolerance(0.01))
+            .into_path()
+    }
+
+    fn area(path: &Path) -> f32 {
+        approximate_signed_area(0.01, path.iter())
+    }
+
+    fn assert_close(actual: f32, expected: f32) {
+        assert!(
+            (actual - expected).abs() < 0.05,
+            "expected {expected}, got {actual}"
+        );
+    }
+
+    fn assert_bounds(path: &Path, min: (f32, f32), max: (f32, f32)) {
+        let bounds = bounding_box(path.iter());
+        assert_close(bounds.min.x, min.0);
+        assert_close(bounds.min.y, min.1);
+        assert_close(bounds.max.x, max.0);
+        assert_close(bounds.max.y, max.1);
+    }
+
+    #[test]
+    fn positive_distance_grows_area() {
+        let miter = offset(&square(10.0), 1.0, OffsetOptions::default());
+        assert_bounds(&miter, (-1.0, -1.0), (11.0, 11.0));
+        assert_close(area(&miter), 144.0);
+    }
+
+    #[test]
+    fn negative_distance_shrinks_area() {
+        for line_join in [LineJoin::Miter, LineJoin::Round, LineJoin::Bevel] {
+            let inset = offset(
+                &square(10.0),
+                -1.0,
+                OffsetOptions::default().with_line_join(line_join),
+            );
+            assert_bounds(&inset, (1.0, 1.0), (9.0, 9.0));
+            assert_close(area(&inset), 64.0);
+        }
+    }
+
+    #[test]
+    fn line_joins() {
+        let square = square(10.0);
+        let round = offset(
+            &square,
+            1.0,
+            OffsetOptions::default().with_line_join(LineJoin::Round),
+        );
+        assert_bounds(&round, (-1.0, -1.0), (11.0, 11.0));
+        assert_close(area(&round), 140.0 + PI);
+
+        let bevel = offset(
+            &square,
+            1.0,
+            OffsetOptions::default().with_line_join(LineJoin::Bevel),
+        );
+        assert_bounds(&bevel, (-1.0, -1.0), (11.0, 11.0));
+        assert_close(area(&bevel), 142.0);
+
+        // Right angles have a miter ratio of about 1.41,
+        // so a lower limit bevels them.
+        let limited = offset(&square, 1.0, OffsetOptions::default().with_miter_limit(1.2));
+        assert_close(area(&limited), 142.0);
+    }
+
+    #[test]
+    fn thin_parts_collapse() {
+        let bar = polygon(&[(0.0, 0.0), (10.0, 0.0), (10.0, 1.0), (0.0, 1.0)], true);
+        assert_eq!(
+            offset(&bar, -1.0, OffsetOptions::default()).iter().count(),
+            0
+        );
+
+        // Two squares joined by a thin bridge are split apart.
+        let dumbbell = polygon(
+            &[
+                (0.0, 0.0),
+                (4.0, 0.0),
+                (4.0, 1.75),
+                (6.0, 1.75),
+                (6.0, 0.0),
+                (10.0, 0.0),
+                (10.0, 4.0),
+                (6.0, 4.0),
+                (6.0, 2.25),
+                (4.0, 2.25),
+                (4.0, 4.0),
+                (0.0, 4.0),
+            ],
+            true,
+        );
+        let split = offset(&dumbbell, -0.5, OffsetOptions::default());
+        let subpaths = split
+            .iter()
+            .filter(|event| matches!(event, PathEvent::Begin { .. }))
+            .count();
+        assert_eq!(subpaths, 2);
+        assert_close(area(&split), 18.0);
+        assert_bounds(&split, (0.5, 0.5), (9.5, 3.5));
+    }
+
+    #[test]
+    fn open_subpaths_become_bands() {
+        let line = polygon(&[(0.0, 0.0), (10.0, 0.0)], false);
+        let butt = offset(&line, 1.0, OffsetOptions::default());
+        assert_bounds(&butt, (0.0, -1.0), (10.0, 1.0));
+        assert_close(area(&butt), 20.0);
+
+        let square = offset(
+            &line,
+            1.0,
+            OffsetOptions::default().with_line_cap(LineCap::Square),
+        );
+        assert_bounds(&square, (-1.0, -1.0), (11.0, 1.0));
+        assert_close(area(&square), 24.0);
+
+        let round = offset(
+            &line,
+            1.0,
+            OffsetOptions::default().with_line_cap(LineCap::Round),
+        );
+        assert_bounds(&round, (-1.0, -1.0), (11.0, 1.0));
+        assert_close(area(&round), 20.0 + PI);
+
+        // Negative distances give the same band.
+        assert_close(area(&offset(&line, -1.0, OffsetOptions::default())), 20.0);
+    }
+}
@@ -0,0 +1,312 @@
+//! Overlap and intersection queries between shapes.
+//!
+//! Pairs of shapes can be tested with the methods of [`Shape`],
+//! or many shapes at once with the [`ShapeOverlaps`] system parameter,
+//! which discards distant pairs with their [`ShapeBounds`].
+//!
+//! Shapes are tested in world space on the XY plane,
+//! after flattening their curves with the tolerance of their fill,
+//! or of their stroke.
+
+use bevy::{ecs::system::SystemParam, prelude::*};
+use lyon_tessellation::{
+    geom::LineSegment,
+    math::{Box2D, Point},
+    path::{iterator::PathIterator, PathEvent},
+};
+
+use crate::{bounds::ShapeBounds, entity::Shape, utils::ToPoint};
+
+impl Shape {
+    /// Returns the points where the outline of this shape, placed by
+    /// `transform`, crosses the outline of `other`, placed by
+    /// `other_transform`.
+    ///
+    /// Points are in world space.
+    /// Points closer than the flattening tolerance are merged.
+    #[must_use]
+    pub fn intersections(
+        &self,
+        transform: &GlobalTransform,
+        other: &Self,
+        other_transform: &GlobalTransform,
+    ) -> Vec<Vec2> {
+        let a = Outline::new(self, transform);
+        let b = Outline::new(other, other_transform);
+        // Crossings at the vertices of flattened curves are found on both
+        // adjacent segments.
+        let merge_distance = self.tolerance().min(other.tolerance());
+        let mut points: Vec<Vec2> = Vec::new();
+        a.for_each_intersection(&b, false, |point| {
+            if !points
+                .iter()
+                .any(|found| found.distance(point) < merge_distance)
+            {
+                points.push(point);
+            }
+            false
+        });
+        points
+    }
+
+    /// Returns `true` if the fill of this shape, placed by `transform`,
+    /// overlaps the fill of `other`, placed by `other_transform`.
+    ///
+    /// Fills that only touch are considered overlapping.
+    /// Always returns `false` if any of the shapes has no fill.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bevy::{color::palettes::css::*, prelude::*};
+    /// use bevy_prototype_lyon::prelude::*;
+    ///
+    /// let circle = shapes::Circle {
+    ///     radius: 10.0,
+    ///     ..default()
+    /// };
+    /// let shape = ShapeBuilder::with(&circle).fill(RED).build();
+    ///
+    /// let near = GlobalTransform::from_xyz(15.0, 0.0, 0.0);
+    /// let far = GlobalTransform::from_xyz(25.0, 0.0, 0.0);
+    /// assert!(shape.overlaps(&GlobalTransform::IDENTITY, &shape, &near));
+    /// assert!(!shape.overlaps(&GlobalTransform::IDENTITY, &shape, &far));
+    /// ```
+    #[must_use]
+    pub fn overlaps(
+        &self,
+        transform: &GlobalTransform,
+        other: &Self,
+        other_transform: &GlobalTransform,
+    ) -> bool {
+        if self.fill.is_none() || other.fill.is_none() {
+            return false;
+        }
+        let a = Outline::new(self, transform);
+        let b = Outline::new(other, other_transform);
+
+        let mut crossing = false;
+        a.for_each_intersection(&b, true, |_| {
+            crossing = true;
+            true
+        });
+        // Without crossings, the fills overlap only if one contains the other.
+        crossing || a.any_vertex_in(other, other_transform) || b.any_vertex_in(self, transform)
+    }
+}
+
+/// Outline of a shape, flattened in world space.
+struct Outline {
+    /// Polylines of the subpaths, with a flag telling whether they are
+    /// closed.
+    contours: Vec<(Vec<Vec2>, bool)>,
+}
+
+impl Outline {
+    fn new(shape: &Shape, transform: &GlobalTransform) -> Self {
+        let tolerance = shape.tolerance();
+        let affine = transform.affine();
+        let to_world = |p: Point| affine.transform_point3(Vec3::new(p.x, p.y, 0.0)).truncate();
+
+        let mut contours = Vec::new();
+        let mut current = Vec::new();
+        for event in shape.path.iter().flattened(tolerance) {
+            match event {
+                PathEvent::Begin { at } => current = vec![to_world(at)],
+                PathEvent::Line { to, .. } => current.push(to_world(to)),
+                PathEvent::End { close, .. } => {
+                    contours.push((std::mem::take(&mut current), close));
+                }
+                _ => {}
+            }
+        }
+        Self { contours }
+    }
+
+    /// Returns the segments of the outline.
+    ///
+    /// If `fill` is `true`, all subpaths are closed, as they are when
+    /// filled.
+    fn segments(&self, fill: bool) -> impl Iterator<Item = LineSegment<f32>> + '_ {
+        self.contours.iter().flat_map(move |(points, close)| {
+            let closing = (fill || *close) && points.len() > 2;
+            points
+                .windows(2)
+                .map(|pair| (pair[0], pair[1]))
+                .chain(closing.then(|| (points[points.len() - 1], points[0])))
+                .map(|(from, to)| LineSegment {
+                    from: from.to_point(),
+                    to: to.to_point(),
+                })
+        })
+    }
+
+    /// Calls `f` on the intersections with the outline of `other`,
+    /// until it returns `true`.
+    fn for_each_intersection(&self, other: &Self, fill: bool, mut f: impl FnMut(Vec2) -> bool) {
+        let others: Vec<_> = other.segments(fill).collect();
+        for segment in self.segments(fill) {
+            let bounds = segment.bounding_box();
+            for other in &others {
+                if !boxes_touch(&bounds, &other.bounding_box()) {
+                    continue;
+                }
+                if let Some(point) = segment.intersection(other) {
+                    if f(Vec2::new(point.x, point.y)) {
+                        return;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Returns `true` if a vertex of any subpath is inside the fill of
+    /// `shape`, placed by `transform`.
+    fn any_vertex_in(&self, shape: &Shape, transform: &GlobalTransform) -> bool {
+        let world_to_local = transform.affine().inverse();
+        self.contours
+            .iter()
+            .filter_map(|(points, _)| points.first())
+            .any(|point| {
+                let local = world_to_local
+                    .transform_point3(point.extend(0.0))
+                    .truncate();
+                shape.fill_contains(local)
+            })
+    }
+}
+
+/// Returns `true` if two boxes intersect or touch.
+///
+/// Unlike `Box2D::intersects`, touching boxes are included,
+/// since the boxes of axis-aligned segments are flat.
+fn boxes_touch(a: &Box2D, b: &Box2D) -> bool {
+    a.min.x <= b.max.x && b.min.x <= a.max.x && a.min.y <= b.max.y && b.min.y <= a.max.y
+}
+
+/// `SystemParam` to test overlaps between shapes in world space,
+/// through their [`GlobalTransform`].
+///
+/// Pairs of shapes whose [`ShapeBounds`] do not touch are discarded
+/// before testing their geometry.
+///
+/// # Example
+///
+/// ```
+/// use bevy::prelude::*;
+/// use bevy_prototype_lyon::prelude::*;
+///
+/// fn collision_system(overlaps: ShapeOverlaps) {
+///     for (a, b) in overlaps.pairs() {
+///         info!("{a} collides with {b}");
+///     }
+/// }
+/// ```
+#[derive(SystemParam)]
+pub struct ShapeOverlaps<'w, 's> {
+    shapes: Query<
+        'w,
+        's,
+        (
+            Entity,
+            &'static Shape,
+            &'static GlobalTransform,
+            &'static ShapeBounds,
+        ),
+    >,
+}
+
+impl ShapeOverlaps<'_, '_> {
+    /// Returns `true` if the fills of the shapes `a` and `b` overlap.
+    ///
+    /// Returns `false` if any of the entities is not a shape.
+    #[must_use]
+    pub fn overlaps(&self, a: Entity, b: Entity) -> bool {
+        let (Ok((_, a, a_transform, a_bounds)), Ok((_, b, b_transform, b_bounds))) =
+            (self.shapes.get(a), self.shapes.get(b))
+        else {
+            return false;
+        };
+        bounds_touch(a_bounds, b_bounds) && a.overlaps(a_transform, b, b_transform)
+    }
+
+    /// Returns the points, in world space,
+    /// where the outlines of the shapes `a` and `b` cross.
+    ///
+    /// Returns no points if any of the entities is not a shape.
+    #[must_use]
+    pub fn intersections(&self, a: Entity, b: Entity) -> Vec<Vec2> {
+        let (Ok((_, a, a_transform, a_bounds)), Ok((_, b, b_transform, b_bounds))) =
+            (self.shapes.get(a), self.shapes.get(b))
+        else {
+            return Vec::new();
+        };
+        if !bounds_touch(a_bounds, b_bounds) {
+            return Vec::new();
+        }
+        a.intersections(a_transform, b, b_transform)
+    }
+
+    /// Returns the shapes whose fill overlaps the fill of `entity`.
+    #[must_use]
+    pub fn overlapping(&self, entity: Entity) -> Vec<Entity> {
+        let Ok((_, shape, transform, bounds)) = self.shapes.get(entity) else {
+            return Vec::new();
+        };
+        self.shapes
+            .iter()
+            .filter(|(other, other_shape, other_transform, other_bounds)| {
+                *other != entity
+                    && bounds_touch(bounds, other_bounds)
+                    && shape.overlaps(transform, other_shape, other_transform)
+            })
+            .map(|(other, ..)| other)
+            .collect()
+    }
+
+    /// Returns all the pairs of shapes whose fills overlap.
+    #[must_use]
+    pub fn pairs(&self) -> Vec<(Entity, Entity)> {
+        // Sweep along the X axis, so only shapes with overlapping X ranges
+        // are tested.
+        // Shapes whose bounds are not computed yet are sorted first,
+        // so they are tested against all the others.
+        let sweep_start = |bounds: &ShapeBounds| {
+            let world = bounds.world();
+            if world.is_empty() {
+                f32::NEG_INFINITY
+            } else {
+                world.min.x
+            }
+        };
+        let mut shapes: Vec<_> = self.shapes.iter().collect();
+        shapes.sort_by(|(.., a), (.., b)| sweep_start(a).total_cmp(&sweep_start(b)));
+        let mut pairs = Vec::new();
+        for (i, (a, a_shape, a_transform, a_bounds)) in shapes.iter().enumerate() {
+            for (b, b_shape, b_transform, b_bounds) in &shapes[i + 1..] {
+                if !a_bounds.world().is_empty() && b_bounds.world().min.x > a_bounds.world().max.x {
+                    break;
+                }
+                if bounds_touch(a_bounds, b_bounds)
+                    && a_shape.overlaps(a_transform, b_shape, b_transform)
+                {
+                    pairs.push((*a, *b));
+                }
+            }
+        }
+        pairs
+    }
+}
+
+/// Returns `true` if the world bounds of two shapes touch.
+///
+/// Bounds that are not computed yet touch any other bounds.
+fn bounds_touch(a: &ShapeBounds, b: &ShapeBounds) -> bool {
+    let (a, b) = (a.world(), b.world());
+    a.is_empty()
+        || b.is_empty()
+        || boxes_touch(
+            &Box2D::new(a.min.to_point(), a.max.to_point()),
+            &Box2D::new(b.min.to_point(), b.max.to_point()),
+        )
+}
@@ -1,12 +1,17 @@
 //! Types outputting lyon `Path`s.
 
-use bevy::math::Vec2;
+use bevy::math::{Affine2, Vec2};
 use lyon_tessellation::{
-    geom::Angle,
-    path::{builder::WithSvg, path::BuilderImpl},
+    geom::{
+        euclid::approxeq::ApproxEq, Angle, Arc, CubicBezierSegment, LineSegment,
+        QuadraticBezierSegment,
+    },
+    math::Point,
+    path::{builder::WithSvg, path::BuilderImpl, Path, PathEvent},
 };
 
 use crate::{
+    distance::Segment,
     prelude::Geometry,
     utils::{ToPoint, ToVector},
 };
@@ -96,6 +101,318 @@ impl ShapePath {
         self.actions.push(Action::Close);
         self
     }
+
+    /// Applies an affine transform to the path.
+    ///
+    /// Arcs are kept under transforms preserving angles.
+    /// Under other transforms, such as skews or non-uniform scales,
+    /// they are converted to cubic bézier curves.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bevy::{math::Affine2, prelude::*};
+    /// use bevy_prototype_lyon::prelude::*;
+    ///
+    /// let arrow = ShapePath::new()
+    ///     .move_to(Vec2::new(0.0, -10.0))
+    ///     .line_to(Vec2::new(20.0, 0.0))
+    ///     .line_to(Vec2::new(0.0, 10.0))
+    ///     .close();
+    /// let pointing_up = arrow.clone().rotate(std::f32::consts::FRAC_PI_2);
+    /// let sheared = arrow.transform(Affine2::from_cols_array(&[1.0, 0.0, 0.5, 1.0, 0.0, 0.0]));
+    /// ```
+    #[must_use]
+    pub fn transform(self, transform: Affine2) -> Self {
+        let mut actions = Vec::with_capacity(self.actions.len());
+        let mut start = Point::zero();
+        let mut current = Point::zero();
+        for action in self.actions {
+            // Arcs are drawn from the current point,
+            // so their segments are needed to convert them.
+            let mut segments = Vec::new();
+            match action {
+                Action::MoveTo(to) => {
+                    start = to.to_point();
+                    current = start;
+                }
+                Action::LineTo(to)
+                | Action::QuadraticBezierTo { to, .. }
+                | Action::CubicBezierTo { to, .. } => current = to.to_point(),
+                Action::Arc {
+                    center,
+                    radii,
+                    sweep_angle,
+                    x_rotation,
+                } => {
+                    current = push_arc(
+                        &mut segments,
+                        current,
+                        center,
+                        radii,
+                        sweep_angle,
+                        x_rotation,
+                    );
+                }
+                Action::Close => current = start,
+            }
+            if let Some(action) = action.transformed(transform) {
+                actions.push(action);
+            } else {
+                actions.extend(
+                    segments
+                        .iter()
+                        .flat_map(segment_actions)
+                        .filter_map(|action| action.transformed(transform)),
+                );
+            }
+        }
+        Self { actions }
+    }
+
+    /// Moves the path by `translation`.
+    #[must_use]
+    pub fn translate(self, translation: Vec2) -> Self {
+        self.transform(Affine2::from_translation(translation))
+    }
+
+    /// Rotates the path by `angle`, in radians, around the origin.
+    #[must_use]
+    pub fn rotate(self, angle: f32) -> Self {
+        self.transform(Affine2::from_angle(angle))
+    }
+
+    /// Scales the path by `scale` from the origin.
+    ///
+    /// Negative factors mirror the path.
+    #[must_use]
+    pub fn scale(self, scale: Vec2) -> Self {
+        self.transform(Affine2::from_scale(scale))
+    }
+
+    /// Skews the path by the angles, in radians,
+    /// between the X axis and the skewed Y axis,
+    /// and between the Y axis and the skewed X axis.
+    #[must_use]
+    pub fn skew(self, angles: Vec2) -> Self {
+        self.transform(Affine2::from_cols_array(&[
+            1.0,
+            angles.y.tan(),
+            angles.x.tan(),
+            1.0,
+            0.0,
+            0.0,
+        ]))
+    }
+
+    /// Mirrors the path across the Y axis, negating X coordinates.
+    #[must_use]
+    pub fn flip_x(self) -> Self {
+        self.scale(Vec2::new(-1.0, 1.0))
+    }
+
+    /// Mirrors the path across the X axis, negating Y coordinates.
+    #[must_use]
+    pub fn flip_y(self) -> Self {
+        self.scale(Vec2::new(1.0, -1.0))
+    }
+
+    /// Reverses the direction of the path,
+    /// and therefore the winding of its subpaths.
+    ///
+    /// Subpaths are also drawn in reverse order,
+    /// and arcs are converted to bézier curves.
+    #[must_use]
+    pub fn reverse(self) -> Self {
+        let vec2 = |p: Point| Vec2::new(p.x, p.y);
+        let actions = self
+            .build()
+            .reversed()
+            .filter_map(|event| match event {
+                PathEvent::Begin { at } => Some(Action::MoveTo(vec2(at))),
+                PathEvent::Line { to, .. } => Some(Action::LineTo(vec2(to))),
+                PathEvent::Quadratic { ctrl, to, .. } => Some(Action::QuadraticBezierTo {
+                    ctrl: vec2(ctrl),
+                    to: vec2(to),
+                }),
+                PathEvent::Cubic {
+                    ctrl1, ctrl2, to, ..
+                } => Some(Action::CubicBezierTo {
+                    ctrl1: vec2(ctrl1),
+                    ctrl2: vec2(ctrl2),
+                    to: vec2(to),
+                }),
+                PathEvent::End { close, .. } => close.then_some(Action::Close),
+            })
+            .collect();
+        Self { actions }
+    }
+
+    /// Builds the lyon `Path` drawn by the actions.
+    pub(crate) fn build(&self) -> Path {
+        let mut builder = Path::builder().with_svg();
+        self.add_geometry(&mut builder);
+        builder.build()
+    }
+
+    /// Returns the segments drawn by the actions,
+    /// keeping arcs as single segments.
+    ///
+    /// Follows the rules of the lyon SVG builder used by [`Geometry`].
+    pub(crate) fn segments(&self) -> Vec<Segment> {
+        let mut segments = Vec::new();
+        let mut start = Point::zero();
+        let mut current = Point::zero();
+        for action in &self.actions {
+            let from = current;
+            match *action {
+                Action::MoveTo(to) => {
+                    start = to.to_point();
+                    current = start;
+                }
+                Action::LineTo(to) => {
+                    current = to.to_point();
+                    segments.push(Segment::Line(LineSegment { from, to: current }));
+                }
+                Action::QuadraticBezierTo { ctrl, to } => {
+                    current = to.to_point();
+                    segments.push(Segment::Quadratic(QuadraticBezierSegment {
+                        from,
+                        ctrl: ctrl.to_point(),
+                        to: current,
+                    }));
+                }
+                Action::CubicBezierTo { ctrl1, ctrl2, to } => {
+                    current = to.to_point();
+                    segments.push(Segment::Cubic(CubicBezierSegment {
+                        from,
+                        ctrl1: ctrl1.to_point(),
+                        ctrl2: ctrl2.to_point(),
+                        to: current,
+                    }));
+                }
+                Action::Arc {
+                    center,
+                    radii,
+                    sweep_angle,
+                    x_rotation,
+                } => {
+                    current = push_arc(&mut segments, from, center, radii, sweep_angle, x_rotation);
+                }
+                Action::Close => {
+                    if current != start {
+                        segments.push(Segment::Line(LineSegment { from, to: start }));
+                    }
+                    current = start;
+                }
+            }
+        }
+        segments
+    }
+}
+
+/// Pushes the segments of an arc starting from `from`,
+/// like the lyon SVG builder, and returns the end of the arc.
+fn push_arc(
+    segments: &mut Vec<Segment>,
+    from: Point,
+    center: Vec2,
+    radii: Vec2,
+    sweep_angle: f32,
+    x_rotation: f32,
+) -> Point {
+    let center = center.to_point();
+    // The builder skips arcs whose start angle is undefined.
+    if from.approx_eq(&center) {
+        return from;
+    }
+    let x_rotation = Angle::radians(x_rotation);
+    let arc = Arc {
+        center,
+        radii: radii.to_vector(),
+        start_angle: (from - center).angle_from_x_axis() - x_rotation,
+        sweep_angle: Angle::radians(sweep_angle),
+        x_rotation,
+    };
+    if !from.approx_eq(&arc.from()) {
+        segments.push(Segment::Line(LineSegment {
+            from,
+            to: arc.from(),
+        }));
+    }
+    segments.push(Segment::Arc(arc));
+    arc.to()
+}
+
+impl Action {
+    /// Applies an affine transform to the action.
+    ///
+    /// Returns `None` for arcs, if the transform does not preserve angles.
+    fn transformed(self, transform: Affine2) -> Option<Self> {
+        let point = |p: Vec2| transform.transform_point2(p);
+        Some(match self {
+            Self::MoveTo(to) => Self::MoveTo(point(to)),
+            Self::LineTo(to) => Self::LineTo(point(to)),
+            Self::QuadraticBezierTo { ctrl, to } => Self::QuadraticBezierTo {
+                ctrl: point(ctrl),
+                to: point(to),
+            },
+            Self::CubicBezierTo { ctrl1, ctrl2, to } => Self::CubicBezierTo {
+                ctrl1: point(ctrl1),
+                ctrl2: point(ctrl2),
+                to: point(to),
+            },
+            Self::Arc {
+                center,
+                radii,
+                sweep_angle,
+                x_rotation,
+            } => {
+                let (x_axis, y_axis) = (transform.matrix2.x_axis, transform.matrix2.y_axis);
+                let epsilon = 1e-5 * x_axis.length_squared();
+                if x_axis.dot(y_axis).abs() > epsilon
+                    || (x_axis.length_squared() - y_axis.length_squared()).abs() > epsilon
+                {
+                    return None;
+                }
+                let determinant = transform.matrix2.determinant();
+                let angle = x_axis.to_angle();
+                // Mirroring reverses the sweep, and the rotation of the radii.
+                let (sweep_angle, x_rotation) = if determinant < 0.0 {
+                    (-sweep_angle, angle - x_rotation)
+                } else {
+                    (sweep_angle, angle + x_rotation)
+                };
+                Self::Arc {
+                    center: point(center),
+                    radii: radii * determinant.abs().sqrt(),
+                    sweep_angle,
+                    x_rotation,
+                }
+            }
+            Self::Close => Self::Close,
+        })
+    }
+}
+
+/// Returns the line and cubic bézier actions drawing a line or an arc.
+fn segment_actions(segment: &Segment) -> Vec<Action> {
+    let vec2 = |p: Point| Vec2::new(p.x, p.y);
+    match segment {
+        Segment::Line(line) => vec![Action::LineTo(vec2(line.to))],
+        Segment::Arc(arc) => {
+            let mut actions = Vec::new();
+            arc.for_each_cubic_bezier(&mut |curve| {
+                actions.push(Action::CubicBezierTo {
+                    ctrl1: vec2(curve.ctrl1),
+                    ctrl2: vec2(curve.ctrl2),
+                    to: vec2(curve.to),
+                });
+            });
+            actions
+        }
+        Segment::Quadratic(_) | Segment::Cubic(_) => Vec::new(),
+    }
 }
 
 impl Geometry<WithSvg<BuilderImpl>> for ShapePath {
@@ -135,5 +452,146 @@ fn match_action(action: Action, b: &mut WithSvg<BuilderImpl>) {
         Action::Close => {
             b.close();
         }
-    };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::{FRAC_PI_2, PI};
+
+    use lyon_algorithms::{aabb::bounding_box, area::approximate_signed_area};
+
+    use super::*;
+    use crate::utils::ToTransform;
+
+    /// A quarter of a disk, drawn with an arc.
+    fn pie() -> ShapePath {
+        ShapePath::new()
+            .move_to(Vec2::ZERO)
+            .line_to(Vec2::new(10.0, 0.0))
+            .arc(Vec2::ZERO, Vec2::splat(10.0), FRAC_PI_2, 0.0)
+            .close()
+    }
+
+    fn area(path: &Path) -> f32 {
+        approximate_signed_area(0.001, path.iter())
+    }
+
+    /// Asserts that `actual` is within 1% of `expected`,
+    /// since lyon approximates arcs with quadratic bézier curves.
+    fn assert_close(actual: f32, expected: f32) {
+        assert!(
+            (actual - expected).abs() <= 0.01 * expected.abs(),
+            "{actual} instead of {expected}"
+        );
+    }
+
+    fn has_arc(path: &ShapePath) -> bool {
+        path.actions
+            .iter()
+            .any(|action| matches!(action, Action::Arc { .. }))
+    }
+
+    /// Asserts that transforming `path` gives the same geometry as
+    /// transforming the lyon path it builds.
+    fn assert_transformed(path: &ShapePath, transform: Affine2) {
+        let expected = path.build().transformed(&transform.to_transform());
+        let actual = path.clone().transform(transform).build();
+        assert_close(area(&actual), area(&expected));
+        let (actual, expected) = (bounding_box(actual.iter()), bounding_box(expected.iter()));
+        assert!(
+            (actual.min - expected.min).length() < 0.1
+                && (actual.max - expected.max).length() < 0.1,
+            "bounds {actual:?} instead of {expected:?}"
+        );
+    }
+
+    #[test]
+    fn arcs_are_kept_under_similarities() {
+        let pie = pie();
+        let transforms = [
+            Affine2::from_translation(Vec2::new(5.0, -3.0)),
+            Affine2::from_angle(1.0),
+            Affine2::from_scale(Vec2::splat(2.5)),
+            Affine2::from_scale_angle_translation(Vec2::splat(0.5), -2.0, Vec2::ONE),
+        ];
+        for transform in transforms {
+            assert!(has_arc(&pie.clone().transform(transform)));
+            assert_transformed(&pie, transform);
+        }
+        assert_close(area(&pie.scale(Vec2::splat(2.0)).build()), 100.0 * PI);
+    }
+
+    #[test]
+    fn arcs_are_kept_under_mirroring() {
+        let pie = pie();
+        for mirrored in [pie.clone().flip_x(), pie.clone().flip_y()] {
+            assert!(has_arc(&mirrored));
+            // Mirroring reverses the winding.
+            assert_close(area(&mirrored.build()), -25.0 * PI);
+        }
+        assert_transformed(&pie, Affine2::from_scale(Vec2::new(-1.0, 1.0)));
+        assert_transformed(
+            &pie,
+            Affine2::from_scale_angle_translation(Vec2::new(2.0, -2.0), 0.7, Vec2::ZERO),
+        );
+    }
+
+    #[test]
+    fn arcs_are_converted_under_other_transforms() {
+        let pie = pie();
+        let transforms = [
+            Affine2::from_scale(Vec2::new(2.0, 1.0)),
+            Affine2::from_cols_array(&[1.0, 0.0, 0.5, 1.0, 0.0, 0.0]),
+            Affine2::from_scale(Vec2::new(-3.0, 1.0)),
+        ];
+        for transform in transforms {
+            let transformed = pie.clone().transform(transform);
+            assert!(!has_arc(&transformed));
+            assert!(transformed
+                .actions
+                .iter()
+                .any(|action| matches!(action, Action::CubicBezierTo { .. })));
+            assert_transformed(&pie, transform);
+        }
+        assert!(!has_arc(&pie.skew(Vec2::new(0.3, 0.0))));
+    }
+
+    #[test]
+    fn elliptical_arcs_rotate_with_the_path() {
+        let ellipse = ShapePath::new()
+            .move_to(Vec2::new(20.0, 0.0))
+            .arc(Vec2::ZERO, Vec2::new(20.0, 10.0), PI, 0.3)
+            .close();
+        assert_transformed(&ellipse, Affine2::from_angle(0.8));
+        assert_transformed(&ellipse, Affine2::from_scale(Vec2::new(1.0, -1.0)));
+        assert_transformed(&ellipse, Affine2::from_scale(Vec2::new(1.0, 3.0)));
+    }
+
+    #[test]
+    fn reverse() {
+        let path = ShapePath::new()
+            .move_to(Vec2::ZERO)
+            .line_to(Vec2::new(10.0, 0.0))
+            .quadratic_bezier_to(Vec2::new(15.0, 5.0), Vec2::new(10.0, 10.0))
+            .close()
+            .move_to(Vec2::new(20.0, 0.0))
+            .cubic_bezier_to(
+                Vec2::new(25.0, 5.0),
+                Vec2::new(30.0, -5.0),
+                Vec2::new(35.0, 0.0),
+            );
+        let reversed = path.clone().reverse();
+        assert_close(area(&reversed.build()), -area(&path.build()));
+        assert_eq!(
+            reversed.actions[0],
+            Action::MoveTo(Vec2::new(35.0, 0.0)),
+            "subpaths are drawn in reverse order"
+        );
+        assert_close(area(&reversed.reverse().build()), area(&path.build()));
+
+        let reversed = pie().reverse();
+        assert!(!has_arc(&reversed));
+        assert_close(area(&reversed.build()), -25.0 * PI);
+    }
 }
@@ -0,0 +1,202 @@
+//! A [`bevy::picking`] backend for shapes.
+//!
+//! Unlike picking with mesh or sprite bounds,
+//! shapes are hit only inside their fill or their stroke,
+//! as tested by [`Shape::contains`].
+
+use std::cmp::Reverse;
+
+use bevy::{
+    math::FloatOrd, picking::backend::prelude::*, prelude::*, utils::HashMap, window::PrimaryWindow,
+};
+
+use crate::{bounds::ShapeBounds, entity::Shape};
+
+/// Plugin that reports [`PointerHits`] on shapes seen by 2D cameras.
+///
+/// Add it alongside [`ShapePlugin`](crate::plugin::ShapePlugin)
+/// so shapes emit picking events like `Pointer<Click>` and `Pointer<Over>`.
+///
+/// Hits are reported for every active camera rendering to the target of the
+/// pointer, from the topmost shape down,
+/// until a shape that blocks lower entities is hit
+/// (see [`PickingBehavior`]).
+/// The part of the shapes that was hit is stored in the [`ShapeHits`]
+/// resource.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct ShapePickingPlugin;
+
+impl Plugin for ShapePickingPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ShapeHits>()
+            .add_systems(PreUpdate, shape_picking_system.in_set(PickSet::Backend));
+    }
+}
+
+/// Part of a [`Shape`] hit by a pointer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ShapePart {
+    /// The fill of the shape.
+    Fill,
+    /// The stroke of the shape, drawn over the fill.
+    Stroke,
+}
+
+/// `Resource` with the parts of the shapes hit by each pointer,
+/// through each camera, in the current frame.
+///
+/// # Example
+///
+/// ```
+/// use bevy::prelude::*;
+/// use bevy_prototype_lyon::prelude::*;
+///
+/// fn on_click(trigger: Trigger<Pointer<Click>>, hits: Res<ShapeHits>) {
+///     let part = hits.part(trigger.pointer_id, trigger.hit.camera, trigger.entity());
+///     if part == Some(ShapePart::Stroke) {
+///         info!("Clicked the outline");
+///     }
+/// }
+/// ```
+#[derive(Resource, Debug, Default)]
+pub struct ShapeHits(HashMap<(PointerId, Entity, Entity), ShapePart>);
+
+impl ShapeHits {
+    /// Returns the part of the shape `entity` hit by `pointer`
+    /// through `camera`, if any.
+    #[must_use]
+    pub fn part(&self, pointer: PointerId, camera: Entity, entity: Entity) -> Option<ShapePart> {
+        self.0.get(&(pointer, camera, entity)).copied()
+    }
+}
+
+type PickableShape<'a> = (
+    Entity,
+    &'a Shape,
+    &'a ShapeBounds,
+    &'a GlobalTransform,
+    Option<&'a PickingBehavior>,
+);
+
+#[allow(clippy::type_complexity)]
+fn shape_picking_system(
+    pointers: Query<(&PointerId, &PointerLocation)>,
+    cameras: Query<(Entity, &Camera, &GlobalTransform, &OrthographicProjection)>,
+    primary_window: Query<Entity, With<PrimaryWindow>>,
+    shapes: Query<(
+        Entity,
+        &Shape,
+        &ShapeBounds,
+        &GlobalTransform,
+        Option<&PickingBehavior>,
+        &ViewVisibility,
+    )>,
+    mut hits: ResMut<ShapeHits>,
+    mut output: EventWriter<PointerHits>,
+) {
+    let mut sorted_shapes: Vec<PickableShape<'_>> = shapes
+        .iter()
+        .filter(|(.., transform, _, visibility)| !transform.affine().is_nan() && visibility.get())
+        .map(|(entity, shape, bounds, transform, picking_behavior, _)| {
+            (entity, shape, bounds, transform, picking_behavior)
+        })
+        .collect();
+    sorted_shapes.sort_by_key(|(.., transform, _)| Reverse(FloatOrd(transform.translation().z)));
+
+    let primary_window = primary_window.get_single().ok();
+    hits.0.clear();
+
+    for (pointer, location) in pointers
+        .iter()
+        .filter_map(|(pointer, location)| location.location().map(|loc| (pointer, loc)))
+    {
+        for (camera_entity, camera, camera_transform, projection) in
+            cameras.iter().filter(|(_, camera, ..)| {
+                camera.is_active
+                    && camera
+                        .target
+                        .normalize(primary_window)
+                        .is_some_and(|target| target == location.target)
+            })
+        {
+            let viewport_min = camera
+                .logical_viewport_rect()
+                .map(|viewport| viewport.min)
+                .unwrap_or_default();
+            let Ok(ray) =
+                camera.viewport_to_world(camera_transform, location.position - viewport_min)
+            else {
+                continue;
+            };
+            let ray_end = ray.origin + ray.direction * (projection.far - projection.near);
+            let picks = pick_shapes(&sorted_shapes, ray.origin, ray_end)
+                .into_iter()
+                .map(|(entity, position, normal, part)| {
+                    hits.0.insert((*pointer, camera_entity, entity), part);
+                    // The depth is measured from the near clipping plane.
+                    let depth = -projection.near
+                        - camera_transform
+                            .affine()
+                            .inverse()
+                            .transform_point3(position)
+                            .z;
+                    let hit = HitData::new(camera_entity, depth, Some(position), Some(normal));
+                    (entity, hit)
+                })
+                .collect();
+
+            output.send(PointerHits::new(*pointer, picks, camera.order as f32));
+        }
+    }
+}
+
+/// Returns the entity, world position, normal and part of the shapes
+/// crossed by the segment from `start` to `end`, from the topmost down,
+/// until a shape blocking lower entities is found.
+fn pick_shapes(
+    sorted_shapes: &[PickableShape<'_>],
+    start: Vec3,
+    end: Vec3,
+) -> Vec<(Entity, Vec3, Vec3, ShapePart)> {
+    let mut picks = Vec::new();
+    for (entity, shape, bounds, transform, picking_behavior) in sorted_shapes {
+        let Some(point) = intersect_plane(transform, start, end) else {
+            continue;
+        };
+        let local_bounds = bounds.local();
+        if !local_bounds.is_empty() && !local_bounds.contains(point) {
+            continue;
+        }
+        let part = if shape.stroke_contains(point) {
+            ShapePart::Stroke
+        } else if shape.fill_contains(point) {
+            ShapePart::Fill
+        } else {
+            continue;
+        };
+
+        let position = transform.transform_point(point.extend(0.0));
+        picks.push((*entity, position, *transform.back(), part));
+
+        if picking_behavior.is_none_or(|behavior| behavior.should_block_lower) {
+            break;
+        }
+    }
+    picks
+}
+
+/// Returns the point, in the local space of a shape,
+/// where the segment from `start` to `end` crosses the plane of the shape.
+fn intersect_plane(transform: &GlobalTransform, start: Vec3, end: Vec3) -> Option<Vec2> {
+    let world_to_local = transform.affine().inverse();
+    let start = world_to_local.transform_point3(start);
+    let end = world_to_local.transform_point3(end);
+    if (start.z - end.z).abs() < f32::EPSILON {
+        // The segment is parallel to the shape.
+        return None;
+    }
+    let t = start.z / (start.z - end.z);
+    (0.0..=1.0)
+        .contains(&t)
+        .then(|| start.lerp(end, t).truncate())
+}
@@ -2,17 +2,28 @@
 //!
 //! The [`ShapePlugin`] provides the creation of shapes with minimal
 //! boilerplate.
+//! The [`ShapeMaterialPlugin`] allows shapes to be drawn with custom
+//! materials.
+
+use std::{hash::Hash, marker::PhantomData};
 
 use bevy::{
+    ecs::component::ComponentId,
+    math::Affine2,
     prelude::*,
     render::{mesh::Indices, render_asset::RenderAssetUsages, render_resource::PrimitiveTopology},
+    sprite::{Material2d, Material2dPlugin},
 };
 use lyon_tessellation::{self as tess, BuffersBuilder};
 
 use crate::{
     draw::{Fill, Stroke},
     entity::Shape,
-    vertex::{VertexBuffers, VertexConstructor},
+    gradient::{GradientMaterial, ATTRIBUTE_GRADIENT},
+    morph::ShapeMorph,
+    vertex::{
+        CustomAttributes, ShapeVertexAttributes, StrokeDistances, VertexBuffers, VertexConstructor,
+    },
 };
 
 pub(crate) const COLOR_MATERIAL_HANDLE: Handle<ColorMaterial> =
@@ -24,15 +35,41 @@ pub struct ShapePlugin;
 
 impl Plugin for ShapePlugin {
     fn build(&self, app: &mut App) {
-        let fill_tess = tess::FillTessellator::new();
-        let stroke_tess = tess::StrokeTessellator::new();
-        app.insert_resource(FillTessellator(fill_tess))
-            .insert_resource(StrokeTessellator(stroke_tess))
-            .configure_sets(
-                PostUpdate,
-                BuildShapes.after(bevy::transform::TransformSystem::TransformPropagate),
+        crate::gradient::load_shaders(app);
+
+        app.add_plugins((
+            Material2dPlugin::<GradientMaterial>::default(),
+            ShapeMaterialPlugin::<ColorMaterial>::default(),
+            ShapeMaterialPlugin::<GradientMaterial>::default(),
+        ))
+        .register_type::<ShapeMorph>()
+        .insert_resource(FillTessellator(tess::FillTessellator::new()))
+        .insert_resource(StrokeTessellator(tess::StrokeTessellator::new()))
+        .configure_sets(
+            PostUpdate,
+            BuildShapes.after(bevy::transform::TransformSystem::TransformPropagate),
+        )
+        .add_systems(
+            PostUpdate,
+            (
+                crate::morph::shape_morph_system,
+                default_material_system,
+                (mesh_shapes_system, gradient_material_system),
+                crate::bounds::shape_bounds_system,
             )
-            .add_systems(PostUpdate, mesh_shapes_system.in_set(BuildShapes));
+                .chain()
+                .in_set(BuildShapes),
+        )
+        .add_systems(
+            PostUpdate,
+            crate::follow::follow_path_system
+                .before(bevy::transform::TransformSystem::TransformPropagate),
+        );
+
+        #[cfg(feature = "3d")]
+        crate::shape3d::build(app);
+        #[cfg(feature = "ui")]
+        app.add_plugins(crate::ui::ShapeNodePlugin);
 
         app.world_mut()
             .resource_mut::<Assets<ColorMaterial>>()
@@ -46,6 +83,64 @@ impl Plugin for ShapePlugin {
     }
 }
 
+/// A plugin that allows [`Shape`]s to be drawn with the [`Material2d`] `M`.
+///
+/// Shapes are given the default [`ColorMaterial`] unless they have a
+/// [`MeshMaterial2d`] of a registered material type.
+/// `ColorMaterial` and [`GradientMaterial`] are registered by the
+/// [`ShapePlugin`].
+///
+/// The vertex colors of the shape are stored in
+/// [`Mesh::ATTRIBUTE_COLOR`], so they are available to material shaders
+/// in the `color` field of the vertex output,
+/// under the `VERTEX_COLORS` shader definition.
+///
+/// The [`Material2dPlugin`] for `M` must be added as well,
+/// before or after this plugin.
+/// The `Material2dPlugin` for `ColorMaterial` is added by Bevy's
+/// `SpritePlugin`, and the one for `GradientMaterial` by the `ShapePlugin`.
+///
+/// # Panics
+///
+/// Panics when the app is finished if the [`Material2dPlugin`] for `M`
+/// was not added.
+pub struct ShapeMaterialPlugin<M>(PhantomData<M>);
+
+impl<M> Default for ShapeMaterialPlugin<M> {
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<M: Material2d> Plugin for ShapeMaterialPlugin<M>
+where
+    M::Data: PartialEq + Eq + Hash + Clone,
+{
+    fn build(&self, app: &mut App) {
+        let id = app.world_mut().register_component::<MeshMaterial2d<M>>();
+        app.world_mut()
+            .get_resource_or_init::<ShapeMaterials>()
+            .0
+            .push(id);
+    }
+
+    fn finish(&self, app: &mut App) {
+        assert!(
+            app.is_plugin_added::<Material2dPlugin<M>>(),
+            "`ShapeMaterialPlugin<{0}>` requires `Material2dPlugin<{0}>` to be added",
+            std::any::type_name::<M>()
+        );
+    }
+
+    fn is_unique(&self) -> bool {
+        true
+    }
+}
+
+/// The [`MeshMaterial2d`] components registered by [`ShapeMaterialPlugin`]s.
+#[derive(Resource, Default)]
+struct ShapeMaterials(Vec<ComponentId>);
+
 /// [`SystemSet`] for the system that builds the meshes for newly-added
 /// or changed shapes. Resides in [`PostUpdate`] schedule.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, SystemSet)]
@@ -59,53 +154,216 @@ fn mesh_shapes_system(
     mut meshes: ResMut<Assets<Mesh>>,
     mut fill_tess: ResMut<FillTessellator>,
     mut stroke_tess: ResMut<StrokeTessellator>,
-    mut query: Query<(&Shape, &mut Mesh2d), Changed<Shape>>,
+    mut query: Query<
+        (&Shape, Option<&ShapeVertexAttributes>, &mut Mesh2d),
+        Or<(Changed<Shape>, Changed<ShapeVertexAttributes>)>,
+    >,
+) {
+    for (shape, vertex_attributes, mut mesh) in &mut query {
+        mesh.0 = meshes.add(tessellate_shape(
+            shape,
+            vertex_attributes,
+            &mut fill_tess,
+            &mut stroke_tess,
+        ));
+    }
+}
+
+/// Builds the mesh of a [`Shape`], with its vertices on the XY plane.
+pub(crate) fn tessellate_shape(
+    shape: &Shape,
+    vertex_attributes: Option<&ShapeVertexAttributes>,
+    fill_tess: &mut FillTessellator,
+    stroke_tess: &mut StrokeTessellator,
+) -> Mesh {
+    let mut custom = vertex_attributes.map(|attributes| CustomAttributes::new(&attributes.0));
+    let buffers = tessellate(shape, fill_tess, stroke_tess, custom.as_mut());
+    let mut mesh = build_mesh(&buffers, has_gradient(shape));
+    if let Some(custom) = custom {
+        custom.insert_into(&mut mesh);
+    }
+    mesh
+}
+
+/// Tessellates the fill and the stroke of a [`Shape`].
+pub(crate) fn tessellate(
+    shape: &Shape,
+    fill_tess: &mut FillTessellator,
+    stroke_tess: &mut StrokeTessellator,
+    mut custom: Option<&mut CustomAttributes>,
+) -> VertexBuffers {
+    let mut buffers = VertexBuffers::new();
+    if let Some(fill_mode) = shape.fill {
+        fill(
+            fill_tess,
+            &shape.path,
+            fill_mode,
+            &mut buffers,
+            custom.as_deref_mut(),
+        );
+    }
+    if let Some(stroke_mode) = shape.stroke {
+        stroke(stroke_tess, &shape.path, stroke_mode, &mut buffers, custom);
+    }
+    buffers
+}
+
+/// Gives the default [`ColorMaterial`] to new shapes
+/// without a registered material.
+fn default_material_system(
+    mut commands: Commands,
+    materials: Res<ShapeMaterials>,
+    query: Query<EntityRef, Added<Shape>>,
 ) {
-    for (shape, mut mesh) in &mut query {
-        let mut buffers = VertexBuffers::new();
-        if let Some(fill_mode) = shape.fill {
-            fill(&mut fill_tess, &shape.path, fill_mode, &mut buffers);
+    for entity in &query {
+        if !materials.0.iter().any(|id| entity.contains_id(*id)) {
+            commands
+                .entity(entity.id())
+                .insert(MeshMaterial2d(COLOR_MATERIAL_HANDLE));
         }
-        if let Some(stroke_mode) = shape.stroke {
-            stroke(&mut stroke_tess, &shape.path, stroke_mode, &mut buffers);
+    }
+}
+
+/// Marks the [`GradientMaterial`] inserted by [`gradient_material_system`],
+/// so materials supplied by the user are never modified or removed.
+#[derive(Component)]
+struct DefaultGradientMaterial(AssetId<GradientMaterial>);
+
+/// Swaps the default [`ColorMaterial`] of shapes painted with a gradient
+/// for a [`GradientMaterial`], and back when they no longer are.
+///
+/// Shapes using any other material, including a [`GradientMaterial`]
+/// of their own, are left untouched.
+#[allow(clippy::type_complexity)]
+fn gradient_material_system(
+    mut commands: Commands,
+    mut materials: ResMut<Assets<GradientMaterial>>,
+    query: Query<
+        (
+            Entity,
+            &Shape,
+            Option<&MeshMaterial2d<ColorMaterial>>,
+            Option<&MeshMaterial2d<GradientMaterial>>,
+            Option<&DefaultGradientMaterial>,
+        ),
+        Changed<Shape>,
+    >,
+) {
+    for (entity, shape, color_material, gradient_material, default_material) in &query {
+        let fill = shape.fill.and_then(|f| f.gradient);
+        let stroke = shape.stroke.and_then(|s| s.gradient);
+        let owned = gradient_material
+            .zip(default_material)
+            .is_some_and(|(handle, default)| handle.id() == default.0);
+
+        match (has_gradient(shape), gradient_material) {
+            (true, Some(handle)) if owned => {
+                if let Some(material) = materials.get_mut(&handle.0) {
+                    material.fill = fill;
+                    material.stroke = stroke;
+                }
+            }
+            (true, None) if color_material.is_some_and(|m| m.0 == COLOR_MATERIAL_HANDLE) => {
+                let handle = materials.add(GradientMaterial { fill, stroke });
+                commands
+                    .entity(entity)
+                    .remove::<MeshMaterial2d<ColorMaterial>>()
+                    .insert((DefaultGradientMaterial(handle.id()), MeshMaterial2d(handle)));
+            }
+            (false, Some(_)) if owned => {
+                commands
+                    .entity(entity)
+                    .remove::<(MeshMaterial2d<GradientMaterial>, DefaultGradientMaterial)>()
+                    .insert(MeshMaterial2d(COLOR_MATERIAL_HANDLE));
+            }
+            _ => {}
         }
-        mesh.0 = meshes.add(build_mesh(&buffers));
     }
 }
 
+fn has_gradient(shape: &Shape) -> bool {
+    shape.fill.is_some_and(|f| f.gradient.is_some())
+        || shape.stroke.is_some_and(|s| s.gradient.is_some())
+}
+
+#[allow(clippy::large_types_passed_by_value)] // Gradients make paints large.
 #[allow(clippy::trivially_copy_pass_by_ref)] // lyon takes &FillOptions
 fn fill(
-    tess: &mut ResMut<FillTessellator>,
+    tess: &mut FillTessellator,
     path: &tess::path::Path,
     mode: Fill,
     buffers: &mut VertexBuffers,
+    mut custom: Option<&mut CustomAttributes>,
 ) {
-    if let Err(e) = tess.tessellate_path(
+    if let Some(custom) = custom.as_deref_mut() {
+        custom.begin(path);
+    }
+    let result = tess.tessellate_with_ids(
+        path.id_iter(),
         path,
+        Some(path),
         &mode.options,
-        &mut BuffersBuilder::new(buffers, VertexConstructor { color: mode.color }),
-    ) {
+        &mut BuffersBuilder::new(
+            buffers,
+            VertexConstructor {
+                color: mode.color,
+                gradient_slot: if mode.gradient.is_some() { 1.0 } else { 0.0 },
+                uv: mode.uv.to_affine(path),
+                custom: custom.as_deref_mut(),
+                distances: None,
+            },
+        ),
+    );
+    if let Some(custom) = custom {
+        custom.end(result.is_ok());
+    }
+    if let Err(e) = result {
         error!("FillTessellator error: {:?}", e);
     }
 }
 
+#[allow(clippy::large_types_passed_by_value)] // Gradients make paints large.
 #[allow(clippy::trivially_copy_pass_by_ref)] // lyon takes &StrokeOptions
 fn stroke(
-    tess: &mut ResMut<StrokeTessellator>,
+    tess: &mut StrokeTessellator,
     path: &tess::path::Path,
     mode: Stroke,
     buffers: &mut VertexBuffers,
+    mut custom: Option<&mut CustomAttributes>,
 ) {
-    if let Err(e) = tess.tessellate_path(
+    // Custom attributes and texture coordinates see the trimmed path.
+    let path = &*mode.path(path);
+    let start = buffers.vertices.len();
+    let mut distances = StrokeDistances::new(path);
+    if let Some(custom) = custom.as_deref_mut() {
+        custom.begin(path);
+    }
+    let result = tess.tessellate_with_ids(
+        path.id_iter(),
         path,
+        Some(path),
         &mode.options,
-        &mut BuffersBuilder::new(buffers, VertexConstructor { color: mode.color }),
-    ) {
+        &mut BuffersBuilder::new(
+            buffers,
+            VertexConstructor {
+                color: mode.color,
+                gradient_slot: if mode.gradient.is_some() { 2.0 } else { 0.0 },
+                uv: Affine2::IDENTITY,
+                custom: custom.as_deref_mut(),
+                distances: Some(&mut distances),
+            },
+        ),
+    );
+    if let Some(custom) = custom {
+        custom.end(result.is_ok());
+    }
+    if let Err(e) = result {
         error!("StrokeTessellator error: {:?}", e);
     }
+    distances.apply(&mut buffers.vertices[start..], mode.uv);
 }
 
-fn build_mesh(buffers: &VertexBuffers) -> Mesh {
+fn build_mesh(buffers: &VertexBuffers, with_gradient: bool) -> Mesh {
     let mut mesh = Mesh::new(
         PrimitiveTopology::TriangleList,
         RenderAssetUsages::default(),
@@ -127,12 +385,30 @@ fn build_mesh(buffers: &VertexBuffers) -> Mesh {
             .map(|v| v.color)
             .collect::<Vec<[f32; 4]>>(),
     );
+    mesh.insert_attribute(
+        Mesh::ATTRIBUTE_UV_0,
+        buffers
+            .vertices
+            .iter()
+            .map(|v| v.uv)
+            .collect::<Vec<[f32; 2]>>(),
+    );
+    if with_gradient {
+        mesh.insert_attribute(
+            ATTRIBUTE_GRADIENT,
+            buffers
+                .vertices
+                .iter()
+                .map(|v| [v.position[0], v.position[1], v.gradient_slot])
+                .collect::<Vec<[f32; 3]>>(),
+        );
+    }
 
     mesh
 }
 
 #[derive(Resource, Deref, DerefMut)]
-struct FillTessellator(lyon_tessellation::FillTessellator);
+pub(crate) struct FillTessellator(lyon_tessellation::FillTessellator);
 
 #[derive(Resource, Deref, DerefMut)]
-struct StrokeTessellator(lyon_tessellation::StrokeTessellator);
+pub(crate) struct StrokeTessellator(lyon_tessellation::StrokeTessellator);
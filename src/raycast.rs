@@ -0,0 +1,245 @@
+//! Ray casting against shape outlines.
+//!
+//! Rays can be cast in the local space of a shape with [`Shape::cast_ray`],
+//! or in world space with the [`ShapeRayCast`] system parameter.
+//! Neither needs rendering, so they also work in headless apps.
+
+use bevy::{ecs::system::SystemParam, prelude::*};
+use lyon_algorithms::raycast::{raycast_path, Ray};
+use lyon_tessellation::path::PathEvent;
+
+use crate::{
+    bounds::ShapeBounds,
+    entity::Shape,
+    utils::{ToPoint, ToVector},
+};
+
+/// Intersection of a ray with the outline of a shape.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ShapeRayHit {
+    /// Point where the ray hits the outline.
+    pub point: Vec2,
+    /// Unit normal of the outline at the hit point, facing the ray origin.
+    pub normal: Vec2,
+    /// Distance from the ray origin to the hit point.
+    pub distance: f32,
+}
+
+impl Shape {
+    /// Returns the first intersection of `ray`, in the local space of the
+    /// shape, with the outline of the path, up to `max_distance`.
+    ///
+    /// Curves are flattened with the tolerance of the fill, or of the stroke.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bevy::{color::palettes::css::*, prelude::*};
+    /// use bevy_prototype_lyon::prelude::*;
+    ///
+    /// let rect = shapes::Rectangle {
+    ///     extents: Vec2::splat(20.0),
+    ///     ..default()
+    /// };
+    /// let shape = ShapeBuilder::with(&rect).fill(RED).build();
+    ///
+    /// let hit = shape
+    ///     .cast_ray(Ray2d::new(Vec2::new(-50.0, 0.0), Dir2::X), f32::INFINITY)
+    ///     .unwrap();
+    /// assert_eq!(hit.point, Vec2::new(-10.0, 0.0));
+    /// assert_eq!(hit.normal, Vec2::NEG_X);
+    /// assert_eq!(hit.distance, 40.0);
+    /// ```
+    #[must_use]
+    pub fn cast_ray(&self, ray: Ray2d, max_distance: f32) -> Option<ShapeRayHit> {
+        let tolerance = self.tolerance();
+        // `raycast_path` also tests the closing segment of open subpaths,
+        // so it is collapsed to a point.
+        let events = self.path.iter().map(|event| match event {
+            PathEvent::End {
+                last, close: false, ..
+            } => PathEvent::End {
+                last,
+                first: last,
+                close: false,
+            },
+            event => event,
+        });
+        let lyon_ray = Ray {
+            origin: ray.origin.to_point(),
+            direction: ray.direction.to_vector(),
+        };
+        let hit = raycast_path(&lyon_ray, events, tolerance)?;
+        let point = Vec2::new(hit.position.x, hit.position.y);
+        let distance = (point - ray.origin).dot(*ray.direction);
+        (distance <= max_distance).then(|| ShapeRayHit {
+            point,
+            normal: Vec2::new(hit.normal.x, hit.normal.y),
+            distance,
+        })
+    }
+}
+
+/// Settings for ray casts with [`ShapeRayCast`].
+#[derive(Clone, Copy)]
+pub struct ShapeRayCastSettings<'a> {
+    /// Maximum distance from the ray origin.
+    pub max_distance: f32,
+    /// Whether shapes whose [`ShapeBounds`] are missed by the ray
+    /// are skipped without testing their path.
+    pub use_bounds: bool,
+    /// Only entities for which the filter returns `true` are tested.
+    pub filter: &'a dyn Fn(Entity) -> bool,
+}
+
+impl Default for ShapeRayCastSettings<'_> {
+    fn default() -> Self {
+        Self {
+            max_distance: f32::INFINITY,
+            use_bounds: true,
+            filter: &|_| true,
+        }
+    }
+}
+
+impl<'a> ShapeRayCastSettings<'a> {
+    /// Sets the maximum distance from the ray origin.
+    #[must_use]
+    pub fn with_max_distance(mut self, max_distance: f32) -> Self {
+        self.max_distance = max_distance;
+        self
+    }
+
+    /// Sets whether the bounds of the shapes are tested first.
+    #[must_use]
+    pub fn with_bounds(mut self, use_bounds: bool) -> Self {
+        self.use_bounds = use_bounds;
+        self
+    }
+
+    /// Sets the filter of the tested entities.
+    #[must_use]
+    pub fn with_filter(mut self, filter: &'a dyn Fn(Entity) -> bool) -> Self {
+        self.filter = filter;
+        self
+    }
+}
+
+/// `SystemParam` to cast rays against shapes in world space,
+/// through their [`GlobalTransform`].
+///
+/// Rays are cast at the depth of each shape,
+/// so shapes are expected to lie on the XY plane.
+///
+/// # Example
+///
+/// ```
+/// use bevy::prelude::*;
+/// use bevy_prototype_lyon::prelude::*;
+///
+/// #[derive(Component)]
+/// struct Laser;
+///
+/// fn laser_system(
+///     ray_cast: ShapeRayCast,
+///     lasers: Query<(Entity, &GlobalTransform), With<Laser>>,
+/// ) {
+///     for (laser, transform) in &lasers {
+///         let ray = Ray2d::new(transform.translation().truncate(), Dir2::X);
+///         let skip_laser = |entity| entity != laser;
+///         let settings = ShapeRayCastSettings::default()
+///             .with_max_distance(500.0)
+///             .with_filter(&skip_laser);
+///         if let Some((entity, hit)) = ray_cast.cast_ray(ray, &settings) {
+///             info!("{entity} hit at {}", hit.point);
+///         }
+///     }
+/// }
+/// ```
+#[derive(SystemParam)]
+pub struct ShapeRayCast<'w, 's> {
+    shapes: Query<
+        'w,
+        's,
+        (
+            Entity,
+            &'static Shape,
+            &'static GlobalTransform,
+            &'static ShapeBounds,
+        ),
+    >,
+}
+
+impl ShapeRayCast<'_, '_> {
+    /// Returns the first shape hit by `ray`, in world space,
+    /// along with the hit, also in world space.
+    #[must_use]
+    pub fn cast_ray(
+        &self,
+        ray: Ray2d,
+        settings: &ShapeRayCastSettings<'_>,
+    ) -> Option<(Entity, ShapeRayHit)> {
+        self.shapes
+            .iter()
+            .filter(|(entity, ..)| (settings.filter)(*entity))
+            .filter(|(.., bounds)| {
+                !settings.use_bounds || may_hit(bounds.world(), ray, settings.max_distance)
+            })
+            .filter_map(|(entity, shape, transform, _)| {
+                cast_ray_on(shape, transform, ray, settings.max_distance).map(|hit| (entity, hit))
+            })
+            .min_by(|(_, a), (_, b)| a.distance.total_cmp(&b.distance))
+    }
+}
+
+/// Casts a world space ray on a transformed shape.
+fn cast_ray_on(
+    shape: &Shape,
+    transform: &GlobalTransform,
+    ray: Ray2d,
+    max_distance: f32,
+) -> Option<ShapeRayHit> {
+    let world_to_local = transform.affine().inverse();
+    let origin = world_to_local
+        .transform_point3(ray.origin.extend(transform.translation().z))
+        .truncate();
+    let direction = world_to_local.transform_vector3(ray.direction.extend(0.0));
+    let local_ray = Ray2d {
+        origin,
+        direction: Dir2::new(direction.truncate()).ok()?,
+    };
+    let hit = shape.cast_ray(local_ray, f32::INFINITY)?;
+
+    let affine = transform.affine();
+    let point = affine.transform_point3(hit.point.extend(0.0)).truncate();
+    let distance = (point - ray.origin).dot(*ray.direction);
+    // Normals are transformed through the tangent, which is transformed as
+    // any other vector.
+    let tangent = affine
+        .transform_vector3(hit.normal.perp().extend(0.0))
+        .truncate();
+    let mut normal = tangent.perp().normalize_or_zero();
+    if normal.dot(*ray.direction) > 0.0 {
+        normal = -normal;
+    }
+    (distance <= max_distance).then_some(ShapeRayHit {
+        point,
+        normal,
+        distance,
+    })
+}
+
+/// Returns `false` if `ray` misses `bounds` within `max_distance`.
+///
+/// Bounds that are not computed yet are never missed.
+fn may_hit(bounds: Rect, ray: Ray2d, max_distance: f32) -> bool {
+    if bounds.is_empty() {
+        return true;
+    }
+    let inverse = ray.direction.recip();
+    let t1 = (bounds.min - ray.origin) * inverse;
+    let t2 = (bounds.max - ray.origin) * inverse;
+    let near = t1.min(t2).max_element().max(0.0);
+    let far = t1.max(t2).min_element().min(max_distance);
+    near <= far
+}
@@ -0,0 +1,305 @@
+//! Placement of copies along paths.
+//!
+//! A [`Scatter`] walks along a measured path,
+//! placing copies at regular intervals, optionally jittered.
+//! Copies can be stamped as geometry into a [`ScatterPath`],
+//! or spawned as child entities.
+
+use bevy::{math::Affine2, prelude::*};
+use lyon_algorithms::path::{traits::Build, Builder};
+use lyon_tessellation::path::Path;
+
+use crate::{
+    geometry::{Geometry, LyonPathBuilderExt},
+    measure::PathMeasure,
+    utils::ToTransform,
+};
+
+/// Rules to place copies at regular intervals along a path.
+///
+/// Jitter is pseudo-random, but determined by `seed`,
+/// so the same rules always give the same placements.
+///
+/// # Example
+///
+/// ```
+/// use bevy::{color::palettes::css::*, prelude::*};
+/// use bevy_prototype_lyon::prelude::*;
+///
+/// let trail = ShapePath::new()
+///     .move_to(Vec2::ZERO)
+///     .quadratic_bezier_to(Vec2::new(100.0, 100.0), Vec2::new(200.0, 0.0));
+/// let dot = shapes::Circle {
+///     radius: 3.0,
+///     ..default()
+/// };
+///
+/// let dots = Scatter::new(15.0).stamp(&trail.measure(0.1), &dot);
+/// let shape = ShapeBuilder::with(&dots).fill(BLACK).build();
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Scatter {
+    /// Distance between consecutive copies, along the path.
+    pub spacing: f32,
+    /// Distance of the first copy from the beginning of the path.
+    pub start: f32,
+    /// Maximum distance each copy is moved along the path,
+    /// forwards or backwards.
+    pub jitter: f32,
+    /// Maximum angle, in radians, each copy is rotated by,
+    /// in either direction.
+    pub angle_jitter: f32,
+    /// Whether the X axis of the copies is rotated along the path.
+    pub rotate: bool,
+    /// Seed of the jitter.
+    pub seed: u64,
+}
+
+impl Scatter {
+    /// Creates rules placing copies every `spacing` units,
+    /// rotated along the path, without jitter.
+    #[must_use]
+    pub const fn new(spacing: f32) -> Self {
+        Self {
+            spacing,
+            start: 0.0,
+            jitter: 0.0,
+            angle_jitter: 0.0,
+            rotate: true,
+            seed: 0,
+        }
+    }
+
+    /// Sets the distance of the first copy.
+    #[must_use]
+    pub const fn with_start(mut self, start: f32) -> Self {
+        self.start = start;
+        self
+    }
+
+    /// Sets the maximum distance each copy is moved along the path.
+    #[must_use]
+    pub const fn with_jitter(mut self, jitter: f32) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    /// Sets the maximum angle each copy is rotated by.
+    #[must_use]
+    pub const fn with_angle_jitter(mut self, angle_jitter: f32) -> Self {
+        self.angle_jitter = angle_jitter;
+        self
+    }
+
+    /// Sets whether the copies are rotated along the path.
+    #[must_use]
+    pub const fn with_rotation(mut self, rotate: bool) -> Self {
+        self.rotate = rotate;
+        self
+    }
+
+    /// Sets the seed of the jitter.
+    #[must_use]
+    pub const fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Returns the placements of the copies along the path of `measure`,
+    /// in the space of the path.
+    ///
+    /// Returns no placements if the spacing is not positive.
+    #[must_use]
+    pub fn placements(&self, measure: &PathMeasure) -> Vec<Transform> {
+        self.positions(measure)
+            .into_iter()
+            .map(|(position, angle)| {
+                Transform::from_translation(position.extend(0.0))
+                    .with_rotation(Quat::from_rotation_z(angle))
+            })
+            .collect()
+    }
+
+    /// Stamps copies of `geometry` along the path of `measure`.
+    #[must_use]
+    pub fn stamp<GenericBuilder>(
+        &self,
+        measure: &PathMeasure,
+        geometry: &impl Geometry<GenericBuilder>,
+    ) -> ScatterPath
+    where
+        GenericBuilder: LyonPathBuilderExt + Build<PathType = Path>,
+    {
+        let mut builder = GenericBuilder::new();
+        geometry.add_geometry(&mut builder);
+        let stamp = builder.build();
+
+        let mut builder = Path::builder();
+        for (position, angle) in self.positions(measure) {
+            let transform = Affine2::from_angle_translation(angle, position).to_transform();
+            let copy = stamp.clone().transformed(&transform);
+            builder.extend_from_paths(&[copy.as_slice()]);
+        }
+        ScatterPath {
+            path: builder.build(),
+        }
+    }
+
+    /// Spawns an entity for each copy along the path of `measure`,
+    /// as children of the entity of `parent`.
+    ///
+    /// The bundle of each copy is returned by `bundle`,
+    /// from the placement of the copy.
+    pub fn spawn<B: Bundle>(
+        &self,
+        parent: &mut ChildBuilder,
+        measure: &PathMeasure,
+        mut bundle: impl FnMut(Transform) -> B,
+    ) {
+        for placement in self.placements(measure) {
+            parent.spawn(bundle(placement));
+        }
+    }
+
+    /// Returns the position and the angle of each copy.
+    fn positions(&self, measure: &PathMeasure) -> Vec<(Vec2, f32)> {
+        let mut distances = Vec::new();
+        measure.walk(self.start, self.spacing, |sample| {
+            distances.push(sample.distance);
+            true
+        });
+        distances
+            .into_iter()
+            .zip(0..)
+            .filter_map(|(distance, i)| {
+                let sample = measure.sample(self.jitter.mul_add(self.random(2 * i), distance))?;
+                let mut angle = self.angle_jitter * self.random(2 * i + 1);
+                if self.rotate {
+                    angle += sample.tangent.to_angle();
+                }
+                Some((sample.position, angle))
+            })
+            .collect()
+    }
+
+    /// Returns a pseudo-random number between `-1.0` and `1.0`
+    /// for the given index.
+    fn random(&self, index: u64) -> f32 {
+        // SplitMix64 finalizer.
+        let mut x = self
+            .seed
+            .wrapping_add(index.wrapping_mul(0x9E37_79B9_7F4A_7C15));
+        x = (x ^ (x >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        x = (x ^ (x >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        x ^= x >> 31;
+        (x >> 40) as f32 / (1u64 << 23) as f32 - 1.0
+    }
+}
+
+/// Geometry made of copies stamped along a path by a [`Scatter`].
+#[derive(Debug, Default, Clone)]
+pub struct ScatterPath {
+    path: Path,
+}
+
+impl ScatterPath {
+    /// Returns the resulting lyon `Path`.
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Converts into the resulting lyon `Path`.
+    #[must_use]
+    pub fn into_path(self) -> Path {
+        self.path
+    }
+}
+
+impl Geometry<Builder> for ScatterPath {
+    fn add_geometry(&self, b: &mut Builder) {
+        b.extend_from_paths(&[self.path.as_slice()]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use lyon_tessellation::path::PathEvent;
+
+    use super::*;
+    use crate::{path::ShapePath, shapes::Rectangle};
+
+    const EPSILON: f32 = 1e-3;
+
+    /// A horizontal line of length 100.
+    fn line() -> PathMeasure {
+        ShapePath::new()
+            .move_to(Vec2::ZERO)
+            .line_to(Vec2::new(100.0, 0.0))
+            .measure(0.1)
+    }
+
+    #[test]
+    fn copies_are_spaced_regularly() {
+        let placements = Scatter::new(25.0).with_start(10.0).placements(&line());
+        assert_eq!(placements.len(), 4);
+        for (placement, x) in placements.iter().zip([10.0, 35.0, 60.0, 85.0]) {
+            assert!((placement.translation.x - x).abs() < EPSILON);
+        }
+        assert!(placements
+            .iter()
+            .all(|t| t.rotation.abs_diff_eq(Quat::IDENTITY, EPSILON)));
+    }
+
+    #[test]
+    fn jitter_is_deterministic_for_a_seed() {
+        let scatter = Scatter::new(10.0)
+            .with_jitter(4.0)
+            .with_angle_jitter(0.5)
+            .with_seed(42);
+        let placements = scatter.placements(&line());
+        assert_eq!(placements, scatter.placements(&line()));
+        assert_ne!(placements, scatter.with_seed(7).placements(&line()));
+    }
+
+    #[test]
+    fn jitter_stays_within_bounds() {
+        let scatter = Scatter::new(10.0)
+            .with_start(10.0)
+            .with_jitter(4.0)
+            .with_angle_jitter(0.5)
+            .with_seed(3);
+        for (i, placement) in scatter.placements(&line()).iter().enumerate() {
+            let x = 10.0 * (i + 1) as f32;
+            assert!((placement.translation.x - x).abs() <= 4.0 + EPSILON);
+            let (_, angle) = placement.rotation.to_axis_angle();
+            assert!(angle <= 0.5 + EPSILON);
+        }
+        for index in 0..1000 {
+            assert!((-1.0..=1.0).contains(&scatter.random(index)));
+        }
+    }
+
+    #[test]
+    fn non_positive_spacing_places_nothing() {
+        assert!(Scatter::new(0.0).placements(&line()).is_empty());
+        assert!(Scatter::new(-5.0).placements(&line()).is_empty());
+    }
+
+    #[test]
+    fn stamp_copies_the_geometry_at_each_placement() {
+        let square = Rectangle {
+            extents: Vec2::splat(2.0),
+            ..default()
+        };
+        let scatter = Scatter::new(50.0);
+        let stamped = scatter.stamp(&line(), &square);
+        let subpaths = stamped
+            .path()
+            .iter()
+            .filter(|event| matches!(event, PathEvent::Begin { .. }))
+            .count();
+        assert_eq!(subpaths, scatter.placements(&line()).len());
+        assert_eq!(subpaths, 3);
+    }
+}
@@ -0,0 +1,245 @@
+//! Shapes rendered in 3D scenes.
+//!
+//! A [`Shape3d`] is tessellated like a [`Shape`],
+//! then laid on a [`ShapePlane`] with normals facing out of the plane,
+//! so it can be lit by a [`StandardMaterial`].
+//! Gradients are only evaluated by the 2D
+//! [`GradientMaterial`](crate::gradient::GradientMaterial):
+//! 3D shapes use the color of their first stop.
+//!
+//! This module is available with the `3d` feature.
+
+use bevy::{
+    prelude::*,
+    render::{camera::Camera, mesh::VertexAttributeValues},
+    transform::{helper::TransformHelper, TransformSystem},
+};
+
+use crate::{
+    entity::Shape,
+    plugin::{tessellate_shape, BuildShapes, FillTessellator, StrokeTessellator},
+    vertex::ShapeVertexAttributes,
+};
+
+pub(crate) const STANDARD_MATERIAL_HANDLE: Handle<StandardMaterial> =
+    Handle::weak_from_u128(0x2D84_0F6B_5A13_4E97_B8C2_71E9_3A50_D6F4);
+
+/// The plane of the local space of a [`Shape3d`] its geometry is laid on.
+///
+/// The `x` and `y` axes of the geometry are mapped so that the front face
+/// points towards the positive side of the remaining axis.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ShapePlane {
+    /// Geometry is laid unchanged, facing `+Z`.
+    #[default]
+    XY,
+    /// Geometry is laid on the ground, facing `+Y`.
+    /// The `y` axis of the geometry points towards `-Z`.
+    XZ,
+    /// Geometry is laid on a wall, facing `+X`.
+    /// The `x` axis of the geometry points towards `-Z`.
+    YZ,
+}
+
+impl ShapePlane {
+    /// Returns the rotation from the `XY` plane to this plane.
+    #[must_use]
+    pub fn rotation(self) -> Quat {
+        match self {
+            Self::XY => Quat::IDENTITY,
+            Self::XZ => Quat::from_rotation_x(-std::f32::consts::FRAC_PI_2),
+            Self::YZ => Quat::from_rotation_y(std::f32::consts::FRAC_PI_2),
+        }
+    }
+
+    /// Returns the position on this plane of a point of the geometry.
+    #[must_use]
+    pub fn to_3d(self, point: Vec2) -> Vec3 {
+        match self {
+            Self::XY => point.extend(0.0),
+            Self::XZ => Vec3::new(point.x, 0.0, -point.y),
+            Self::YZ => Vec3::new(0.0, point.y, -point.x),
+        }
+    }
+
+    /// Returns the direction the front face of the plane points to.
+    #[must_use]
+    pub fn normal(self) -> Vec3 {
+        match self {
+            Self::XY => Vec3::Z,
+            Self::XZ => Vec3::Y,
+            Self::YZ => Vec3::X,
+        }
+    }
+}
+
+/// `Component` describing a geometric shape in a 3D scene.
+///
+/// It can be constructed from a [`Shape`] built using `ShapeBuilder`.
+///
+/// Shapes are drawn with a default double-sided [`StandardMaterial`]
+/// that multiplies the vertex colors,
+/// which can be replaced by any other `MeshMaterial3d<StandardMaterial>`.
+///
+/// # Example
+///
+/// ```
+/// use bevy::{color::palettes::css::*, prelude::*};
+/// use bevy_prototype_lyon::prelude::*;
+///
+/// fn setup_system(mut commands: Commands) {
+///     let circle = shapes::Circle {
+///         radius: 2.0,
+///         ..default()
+///     };
+///     let marking = ShapeBuilder::with(&circle).stroke((YELLOW, 0.2)).build();
+///     commands.spawn(Shape3d::new(marking, ShapePlane::XZ));
+/// }
+/// ```
+#[derive(Component, Default, Clone)]
+#[require(Mesh3d, MeshMaterial3d<StandardMaterial>(standard_material_handle), Transform, Visibility)]
+pub struct Shape3d {
+    /// The geometry and the paint of the shape.
+    pub shape: Shape,
+    /// The plane the shape is laid on.
+    pub plane: ShapePlane,
+}
+
+impl Shape3d {
+    /// Creates a shape laid on the given plane.
+    #[must_use]
+    pub fn new(shape: Shape, plane: ShapePlane) -> Self {
+        Self { shape, plane }
+    }
+}
+
+impl From<Shape> for Shape3d {
+    fn from(shape: Shape) -> Self {
+        Self::new(shape, ShapePlane::default())
+    }
+}
+
+fn standard_material_handle() -> MeshMaterial3d<StandardMaterial> {
+    MeshMaterial3d(STANDARD_MATERIAL_HANDLE)
+}
+
+/// `Component` that keeps a [`Shape3d`] facing the camera.
+///
+/// The rotation of the [`Transform`] of the entity is overwritten before
+/// transform propagation, so children follow the shape.
+/// The rotation of the parents is compensated.
+#[derive(Component, Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[require(Transform)]
+pub enum Billboard {
+    /// The shape is parallel to the screen.
+    #[default]
+    Screen,
+    /// The shape only rotates around the `Y` axis, staying upright.
+    Vertical,
+}
+
+/// Adds the resources and systems of 3D shapes to the app.
+pub(crate) fn build(app: &mut App) {
+    app.add_systems(PostUpdate, mesh_shapes_3d_system.in_set(BuildShapes))
+        .add_systems(
+            PostUpdate,
+            billboard_system.before(TransformSystem::TransformPropagate),
+        );
+
+    app.world_mut()
+        .resource_mut::<Assets<StandardMaterial>>()
+        .insert(
+            &STANDARD_MATERIAL_HANDLE,
+            StandardMaterial {
+                base_color: Color::WHITE,
+                cull_mode: None,
+                double_sided: true,
+                ..default()
+            },
+        );
+}
+
+/// Meshes the [`Shape3d`]s when they are added or changed.
+#[allow(clippy::type_complexity)]
+fn mesh_shapes_3d_system(
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut fill_tess: ResMut<FillTessellator>,
+    mut stroke_tess: ResMut<StrokeTessellator>,
+    mut query: Query<
+        (&Shape3d, Option<&ShapeVertexAttributes>, &mut Mesh3d),
+        Or<(Changed<Shape3d>, Changed<ShapeVertexAttributes>)>,
+    >,
+) {
+    for (shape, vertex_attributes, mut mesh) in &mut query {
+        let mut shape_mesh = tessellate_shape(
+            &shape.shape,
+            vertex_attributes,
+            &mut fill_tess,
+            &mut stroke_tess,
+        );
+        lay_on_plane(&mut shape_mesh, shape.plane);
+        mesh.0 = meshes.add(shape_mesh);
+    }
+}
+
+/// Moves the vertices of a mesh built on the `XY` plane to `plane`,
+/// and inserts their normals.
+fn lay_on_plane(mesh: &mut Mesh, plane: ShapePlane) {
+    let Some(VertexAttributeValues::Float32x3(positions)) =
+        mesh.attribute_mut(Mesh::ATTRIBUTE_POSITION)
+    else {
+        return;
+    };
+    for position in positions.iter_mut() {
+        *position = plane.to_3d(Vec2::new(position[0], position[1])).to_array();
+    }
+    let normals = vec![plane.normal().to_array(); positions.len()];
+    mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, normals);
+}
+
+/// Rotates [`Billboard`]s to face the first active 3D camera.
+///
+/// Global transforms are computed from the hierarchy,
+/// since they have not been propagated yet.
+#[allow(clippy::type_complexity)]
+fn billboard_system(
+    cameras: Query<(Entity, &Camera), With<Camera3d>>,
+    billboards: Query<(Entity, &Billboard, Option<&Shape3d>, Option<&Parent>), Without<Camera>>,
+    mut transforms: ParamSet<(TransformHelper, Query<&mut Transform>)>,
+) {
+    let Some((camera, _)) = cameras.iter().find(|(_, camera)| camera.is_active) else {
+        return;
+    };
+    let helper = transforms.p0();
+    let Ok(camera) = helper.compute_global_transform(camera) else {
+        return;
+    };
+    let rotations: Vec<_> = billboards
+        .iter()
+        .filter_map(|(entity, billboard, shape, parent)| {
+            let translation = helper.compute_global_transform(entity).ok()?.translation();
+            let parent_rotation = parent
+                .and_then(|parent| helper.compute_global_transform(parent.get()).ok())
+                .map_or(Quat::IDENTITY, |parent| parent.rotation());
+            let facing = match billboard {
+                Billboard::Screen => camera.rotation(),
+                Billboard::Vertical => {
+                    let direction = camera.translation() - translation;
+                    Quat::from_rotation_y(direction.x.atan2(direction.z))
+                }
+            };
+            let plane = shape.map_or(ShapePlane::XY, |shape| shape.plane);
+            Some((
+                entity,
+                parent_rotation.inverse() * facing * plane.rotation().inverse(),
+            ))
+        })
+        .collect();
+
+    let mut query = transforms.p1();
+    for (entity, rotation) in rotations {
+        if let Ok(mut transform) = query.get_mut(entity) {
+            transform.rotation = rotation;
+        }
+    }
+}
@@ -0,0 +1,417 @@
+//! Simplification and smoothing of polylines.
+//!
+//! The operations work on the points of a [`Polygon`].
+//! A [`ShapePath`] can be flattened into polygons with
+//! [`ShapePath::polygons`], or simplified directly with
+//! [`ShapePath::simplify`].
+
+use bevy::math::Vec2;
+use lyon_tessellation::path::{iterator::PathIterator, PathEvent};
+
+use crate::{path::ShapePath, shapes::Polygon};
+
+impl Polygon {
+    /// Removes the points closer than `tolerance` to the simplified outline,
+    /// with the Ramer–Douglas–Peucker algorithm.
+    ///
+    /// The ends of polylines are kept.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bevy::prelude::*;
+    /// use bevy_prototype_lyon::prelude::*;
+    ///
+    /// let stroke = shapes::Polygon {
+    ///     points: vec![
+    ///         Vec2::new(0.0, 0.0),
+    ///         Vec2::new(5.0, 0.1),
+    ///         Vec2::new(10.0, 0.0),
+    ///         Vec2::new(10.0, 10.0),
+    ///     ],
+    ///     closed: false,
+    /// };
+    /// let simplified = stroke.simplify(0.5);
+    /// assert_eq!(simplified.points.len(), 3);
+    /// ```
+    #[must_use]
+    pub fn simplify(&self, tolerance: f32) -> Self {
+        let points = &self.points;
+        if points.len() < 3 {
+            return self.clone();
+        }
+        let points = if self.closed {
+            // The outline is split at the point farthest from the first,
+            // which is kept as well.
+            let far = (1..points.len())
+                .max_by(|a, b| {
+                    points[0]
+                        .distance_squared(points[*a])
+                        .total_cmp(&points[0].distance_squared(points[*b]))
+                })
+                .unwrap_or(1);
+            let mut closing = points[far..].to_vec();
+            closing.push(points[0]);
+            let mut simplified = douglas_peucker(&points[..=far], tolerance);
+            simplified.pop();
+            simplified.extend(douglas_peucker(&closing, tolerance));
+            simplified.pop();
+            simplified
+        } else {
+            douglas_peucker(points, tolerance)
+        };
+        Self {
+            points,
+            closed: self.closed,
+        }
+    }
+
+    /// Removes the points that form, with their neighbors,
+    /// triangles with an area smaller than `min_area`,
+    /// with the Visvalingam–Whyatt algorithm.
+    ///
+    /// The ends of polylines are kept,
+    /// as well as at least three points of polygons.
+    #[must_use]
+    pub fn simplify_visvalingam(&self, min_area: f32) -> Self {
+        let mut points = self.points.clone();
+        let min_len = if self.closed { 3 } else { 2 };
+        let area = |points: &[Vec2], i: usize| {
+            let n = points.len();
+            let (previous, next) = (points[(i + n - 1) % n], points[(i + 1) % n]);
+            (previous - points[i]).perp_dot(next - points[i]).abs() / 2.0
+        };
+        while points.len() > min_len {
+            let removable = if self.closed {
+                0..points.len()
+            } else {
+                1..points.len() - 1
+            };
+            let Some((index, smallest)) = removable
+                .map(|i| (i, area(&points, i)))
+                .min_by(|(_, a), (_, b)| a.total_cmp(b))
+            else {
+                break;
+            };
+            if smallest >= min_area {
+                break;
+            }
+            points.remove(index);
+        }
+        Self {
+            points,
+            closed: self.closed,
+        }
+    }
+
+    /// Rounds the corners by cutting them `iterations` times,
+    /// with Chaikin's algorithm.
+    ///
+    /// Each iteration replaces every edge with two points at a quarter and
+    /// three quarters of its length.
+    /// The ends of polylines are kept.
+    #[must_use]
+    pub fn smooth(&self, iterations: usize) -> Self {
+        let mut points = self.points.clone();
+        for _ in 0..iterations {
+            if points.len() < 3 {
+                break;
+            }
+            let edges = if self.closed {
+                points.len()
+            } else {
+                points.len() - 1
+            };
+            let mut smoothed = Vec::with_capacity(2 * edges + 2);
+            if !self.closed {
+                smoothed.push(points[0]);
+            }
+            for i in 0..edges {
+                let (from, to) = (points[i], points[(i + 1) % points.len()]);
+                smoothed.push(from.lerp(to, 0.25));
+                smoothed.push(from.lerp(to, 0.75));
+            }
+            if !self.closed {
+                smoothed.push(points[points.len() - 1]);
+            }
+            points = smoothed;
+        }
+        Self {
+            points,
+            closed: self.closed,
+        }
+    }
+
+    /// Returns a smooth path passing through the points,
+    /// made of the cubic bézier curves of a Catmull-Rom spline.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bevy::{color::palettes::css::*, prelude::*};
+    /// use bevy_prototype_lyon::prelude::*;
+    ///
+    /// let outline = shapes::Polygon {
+    ///     points: vec![
+    ///         Vec2::new(0.0, 0.0),
+    ///         Vec2::new(100.0, 20.0),
+    ///         Vec2::new(80.0, 100.0),
+    ///         Vec2::new(10.0, 60.0),
+    ///     ],
+    ///     closed: true,
+    /// };
+    /// let blob = ShapeBuilder::with(&outline.to_smooth_path())
+    ///     .fill(PURPLE)
+    ///     .build();
+    /// ```
+    #[must_use]
+    pub fn to_smooth_path(&self) -> ShapePath {
+        let points = &self.points;
+        let Some(&first) = points.first() else {
+            return ShapePath::new();
+        };
+        let n = points.len();
+        // Returns the point at `offset` from the one at `i`.
+        // Neighbors past the ends of polylines repeat the ends.
+        let point = |i: usize, offset: usize| {
+            if self.closed {
+                points[(i + n + offset - 1) % n]
+            } else {
+                points[(i + offset).saturating_sub(1).min(n - 1)]
+            }
+        };
+        let segments = if self.closed { n } else { n - 1 };
+        let mut path = ShapePath::new().move_to(first);
+        for i in 0..segments {
+            // The offsets are shifted by one, to stay unsigned.
+            let (p0, p1, p2, p3) = (point(i, 0), point(i, 1), point(i, 2), point(i, 3));
+            path = path.cubic_bezier_to(p1 + (p2 - p0) / 6.0, p2 - (p3 - p1) / 6.0, p2);
+        }
+        if self.closed {
+            path = path.close();
+        }
+        path
+    }
+}
+
+impl ShapePath {
+    /// Flattens the subpaths into polygons and polylines,
+    /// approximating curves with `tolerance`.
+    #[must_use]
+    pub fn polygons(&self, tolerance: f32) -> Vec<Polygon> {
+        let mut polygons = Vec::new();
+        let mut points = Vec::new();
+        for event in self.build().iter().flattened(tolerance) {
+            match event {
+                PathEvent::Begin { at } => points = vec![Vec2::new(at.x, at.y)],
+                PathEvent::Line { to, .. } => points.push(Vec2::new(to.x, to.y)),
+                PathEvent::End { close, .. } => {
+                    if close && points.len() > 1 && points.first() == points.last() {
+                        points.pop();
+                    }
+                    polygons.push(Polygon {
+                        points: std::mem::take(&mut points),
+                        closed: close,
+                    });
+                }
+                _ => {}
+            }
+        }
+        polygons
+    }
+
+    /// Returns a path of lines within `tolerance` from this path,
+    /// with as few points as possible.
+    ///
+    /// Curves are flattened, then simplified with [`Polygon::simplify`].
+    #[must_use]
+    pub fn simplify(&self, tolerance: f32) -> Self {
+        // Half the tolerance goes to the flattening,
+        // half to the simplification.
+        let mut path = Self::new();
+        for polygon in self.polygons(tolerance / 2.0) {
+            let polygon = polygon.simplify(tolerance / 2.0);
+            let mut points = polygon.points.iter();
+            let Some(first) = points.next() else {
+                continue;
+            };
+            path = points.fold(path.move_to(*first), |path, point| path.line_to(*point));
+            if polygon.closed {
+                path = path.close();
+            }
+        }
+        path
+    }
+}
+
+/// Simplifies a polyline with the Ramer–Douglas–Peucker algorithm,
+/// keeping its ends.
+fn douglas_peucker(points: &[Vec2], tolerance: f32) -> Vec<Vec2> {
+    let mut keep = vec![false; points.len()];
+    keep[0] = true;
+    keep[points.len() - 1] = true;
+    let mut ranges = vec![(0, points.len() - 1)];
+    while let Some((start, end)) = ranges.pop() {
+        let (from, to) = (points[start], points[end]);
+        let farthest = (start + 1..end)
+            .map(|i| (i, distance_to_segment(points[i], from, to)))
+            .max_by(|(_, a), (_, b)| a.total_cmp(b));
+        if let Some((index, distance)) = farthest {
+            if distance > tolerance {
+                keep[index] = true;
+                ranges.push((start, index));
+                ranges.push((index, end));
+            }
+        }
+    }
+    points
+        .iter()
+        .zip(keep)
+        .filter_map(|(point, keep)| keep.then_some(*point))
+        .collect()
+}
+
+/// Returns the distance from `point` to the segment from `from` to `to`.
+fn distance_to_segment(point: Vec2, from: Vec2, to: Vec2) -> f32 {
+    let vector = to - from;
+    let length = vector.length_squared();
+    let t = if length > 0.0 {
+        ((point - from).dot(vector) / length).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    point.distance(from + vector * t)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn polygon(points: &[(f32, f32)], closed: bool) -> Polygon {
+        Polygon {
+            points: points.iter().map(|point| Vec2::from(*point)).collect(),
+            closed,
+        }
+    }
+
+    #[test]
+    fn few_points_are_kept() {
+        for closed in [false, true] {
+            for points in [&[][..], &[(1.0, 2.0)], &[(0.0, 0.0), (10.0, 0.0)]] {
+                let polygon = polygon(points, closed);
+                assert_eq!(polygon.simplify(1.0), polygon);
+                assert_eq!(polygon.simplify_visvalingam(1.0), polygon);
+                assert_eq!(polygon.smooth(2), polygon);
+            }
+        }
+        assert!(polygon(&[], false)
+            .to_smooth_path()
+            .build()
+            .iter()
+            .next()
+            .is_none());
+    }
+
+    #[test]
+    fn collinear_points_are_removed() {
+        let line = polygon(
+            &[(0.0, 0.0), (1.0, 0.0), (2.0, 0.0), (5.0, 0.0), (6.0, 0.0)],
+            false,
+        );
+        let simplified = line.simplify(0.01);
+        assert_eq!(simplified.points, vec![Vec2::ZERO, Vec2::new(6.0, 0.0)]);
+        assert_eq!(line.simplify_visvalingam(0.01).points, simplified.points);
+    }
+
+    #[test]
+    fn polylines_keep_their_ends() {
+        let zigzag = polygon(
+            &[(0.0, 0.0), (1.0, 0.2), (2.0, -0.2), (3.0, 0.0), (3.0, 5.0)],
+            false,
+        );
+        let simplified = zigzag.simplify(0.5);
+        assert_eq!(
+            simplified.points,
+            vec![Vec2::ZERO, Vec2::new(3.0, 0.0), Vec2::new(3.0, 5.0)]
+        );
+        // A lower tolerance keeps the zigzag.
+        assert_eq!(zigzag.simplify(0.1).points, zigzag.points);
+    }
+
+    #[test]
+    fn closed_rings_keep_their_corners() {
+        // A square with points in the middle of its edges.
+        // It is split at the corner farthest from the first point, (10, 10),
+        // and both halves are simplified to their corners.
+        let ring = polygon(
+            &[
+                (0.0, 0.0),
+                (5.0, 0.0),
+                (10.0, 0.0),
+                (10.0, 5.0),
+                (10.0, 10.0),
+                (5.0, 10.0),
+                (0.0, 10.0),
+                (0.0, 5.0),
+            ],
+            true,
+        );
+        let simplified = ring.simplify(0.1);
+        assert!(simplified.closed);
+        assert_eq!(
+            simplified.points,
+            vec![
+                Vec2::ZERO,
+                Vec2::new(10.0, 0.0),
+                Vec2::new(10.0, 10.0),
+                Vec2::new(0.0, 10.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn thin_closed_rings_are_reduced_to_their_split() {
+        // A thin ring, all of whose points are within the tolerance of
+        // the segment from the first point to the farthest one,
+        // which are the only points kept.
+        let ring = polygon(&[(0.0, 0.0), (5.0, 0.1), (10.0, 0.0), (5.0, -0.1)], true);
+        let simplified = ring.simplify(1.0);
+        assert_eq!(simplified.points, vec![Vec2::ZERO, Vec2::new(10.0, 0.0)]);
+        // Visvalingam's algorithm keeps three points of polygons.
+        assert_eq!(ring.simplify_visvalingam(10.0).points.len(), 3);
+    }
+
+    #[test]
+    fn smooth_cuts_corners() {
+        let corner = polygon(&[(0.0, 0.0), (4.0, 0.0), (4.0, 4.0)], false);
+        assert_eq!(
+            corner.smooth(1).points,
+            vec![
+                Vec2::ZERO,
+                Vec2::new(1.0, 0.0),
+                Vec2::new(3.0, 0.0),
+                Vec2::new(4.0, 1.0),
+                Vec2::new(4.0, 3.0),
+                Vec2::new(4.0, 4.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn path_simplification_stays_within_tolerance() {
+        let path = ShapePath::new()
+            .move_to(Vec2::ZERO)
+            .quadratic_bezier_to(Vec2::new(50.0, 50.0), Vec2::new(100.0, 0.0));
+        let flattened = &path.polygons(0.001)[0];
+        let simplified = &path.simplify(1.0).polygons(0.001)[0];
+        assert!(simplified.points.len() < flattened.points.len());
+        for point in &flattened.points {
+            let distance = simplified
+                .points
+                .windows(2)
+                .map(|segment| distance_to_segment(*point, segment[0], segment[1]))
+                .fold(f32::INFINITY, f32::min);
+            assert!(distance <= 1.0, "{point} is {distance} away");
+        }
+    }
+}
@@ -0,0 +1,275 @@
+//! Affine transforms and direction of geometry.
+//!
+//! Any [`Geometry`] can be added to a shape builder with an affine transform,
+//! by wrapping it into [`Transformed`].
+//! [`ShapePath`](crate::path::ShapePath) and [`Shape`] have their own
+//! transform methods, as well as methods reversing their direction.
+
+use bevy::math::Affine2;
+use lyon_algorithms::{area::approximate_signed_area, hit_test::hit_test_path};
+use lyon_tessellation::{
+    path::{
+        builder::WithSvg,
+        path::{Builder, BuilderImpl},
+        traits::Build,
+        Path, PathEvent, Winding,
+    },
+    FillRule,
+};
+
+use crate::{
+    entity::Shape,
+    geometry::{Geometry, LyonPathBuilderExt},
+    utils::ToTransform,
+};
+
+/// Geometry with an affine transform applied.
+///
+/// Useful to reuse parts in different places of a composite shape.
+///
+/// # Example
+///
+/// ```
+/// use bevy::{color::palettes::css::*, math::Affine2, prelude::*};
+/// use bevy_prototype_lyon::prelude::*;
+///
+/// let petal = shapes::Ellipse {
+///     radii: Vec2::new(10.0, 30.0),
+///     center: Vec2::new(0.0, 30.0),
+/// };
+/// let flower = (0..5)
+///     .fold(ShapeBuilder::new(), |builder, i| {
+///         let angle = i as f32 * std::f32::consts::TAU / 5.0;
+///         builder.add(&Transformed::new(petal, Affine2::from_angle(angle)))
+///     })
+///     .fill(PINK)
+///     .build();
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Transformed<G> {
+    /// Transformed geometry.
+    pub geometry: G,
+    /// Transform applied to the geometry.
+    pub transform: Affine2,
+}
+
+impl<G> Transformed<G> {
+    /// Wraps `geometry`, applying `transform` to it.
+    #[must_use]
+    pub const fn new(geometry: G, transform: Affine2) -> Self {
+        Self {
+            geometry,
+            transform,
+        }
+    }
+
+    /// Returns the path of the geometry, transformed.
+    fn path<B>(&self) -> Path
+    where
+        G: Geometry<B>,
+        B: LyonPathBuilderExt + Build<PathType = Path>,
+    {
+        let mut builder = B::new();
+        self.geometry.add_geometry(&mut builder);
+        builder.build().transformed(&self.transform.to_transform())
+    }
+}
+
+impl<G: Geometry<Builder>> Geometry<Builder> for Transformed<G> {
+    fn add_geometry(&self, b: &mut Builder) {
+        b.extend_from_paths(&[self.path().as_slice()]);
+    }
+}
+
+impl<G: Geometry<WithSvg<BuilderImpl>>> Geometry<WithSvg<BuilderImpl>> for Transformed<G> {
+    fn add_geometry(&self, b: &mut WithSvg<BuilderImpl>) {
+        for event in &self.path() {
+            match event {
+                PathEvent::Begin { at } => {
+                    b.move_to(at);
+                }
+                PathEvent::Line { to, .. } => {
+                    b.line_to(to);
+                }
+                PathEvent::Quadratic { ctrl, to, .. } => {
+                    b.quadratic_bezier_to(ctrl, to);
+                }
+                PathEvent::Cubic {
+                    ctrl1, ctrl2, to, ..
+                } => {
+                    b.cubic_bezier_to(ctrl1, ctrl2, to);
+                }
+                PathEvent::End { close, .. } => {
+                    if close {
+                        b.close();
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl Shape {
+    /// Applies an affine transform to the path.
+    ///
+    /// The stroke width is not scaled along with the path.
+    pub fn transform(&mut self, transform: Affine2) {
+        self.path = self.path.clone().transformed(&transform.to_transform());
+    }
+
+    /// Reverses the direction of the path,
+    /// and therefore the winding of its subpaths.
+    pub fn reverse(&mut self) {
+        self.path = self.path.reversed().collect();
+    }
+
+    /// Reverses the subpaths whose winding does not follow the nesting of
+    /// the subpaths.
+    ///
+    /// Outer subpaths get the given `winding`,
+    /// subpaths inside them the opposite winding, and so on,
+    /// so the path fills the same area with the [`FillRule::NonZero`] fill
+    /// rule as with [`FillRule::EvenOdd`].
+    /// [`Winding::Positive`] is counter-clockwise.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bevy::{color::palettes::css::*, prelude::*};
+    /// use bevy_prototype_lyon::prelude::*;
+    ///
+    /// // Both circles are counter-clockwise,
+    /// // so the inner one is not a hole with the non-zero fill rule.
+    /// let mut ring = ShapeBuilder::new()
+    ///     .add(&shapes::Circle {
+    ///         radius: 50.0,
+    ///         ..default()
+    ///     })
+    ///     .add(&shapes::Circle {
+    ///         radius: 30.0,
+    ///         ..default()
+    ///     })
+    ///     .fill(Fill::color(GOLD).with_options(FillOptions::non_zero()))
+    ///     .build();
+    /// ring.normalize_winding(Winding::Positive);
+    /// ```
+    pub fn normalize_winding(&mut self, winding: Winding) {
+        let tolerance = self.tolerance();
+        let mut subpaths = Vec::new();
+        let mut events = Vec::new();
+        for event in &self.path {
+            events.push(event);
+            if let PathEvent::End { .. } = event {
+                subpaths.push(std::mem::take(&mut events).into_iter().collect::<Path>());
+            }
+        }
+
+        let mut builder = Path::builder();
+        for (i, subpath) in subpaths.iter().enumerate() {
+            let Some((first, _)) = subpath.first_endpoint() else {
+                continue;
+            };
+            let depth = subpaths
+                .iter()
+                .enumerate()
+                .filter(|(j, other)| {
+                    *j != i && hit_test_path(&first, other.iter(), FillRule::EvenOdd, tolerance)
+                })
+                .count();
+            let positive = (winding == Winding::Positive) == (depth % 2 == 0);
+            let area = approximate_signed_area(tolerance, subpath.iter());
+            if area != 0.0 && (area > 0.0) != positive {
+                builder.extend_from_paths(&[subpath.reversed().collect::<Path>().as_slice()]);
+            } else {
+                builder.extend_from_paths(&[subpath.as_slice()]);
+            }
+        }
+        self.path = builder.build();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::{color::palettes::css::GOLD, math::Vec2};
+
+    use super::*;
+    use crate::{
+        draw::Fill,
+        geometry::{ShapeBuilder, ShapeBuilderBase},
+        path::ShapePath,
+    };
+
+    /// A square centered on the origin, counter-clockwise unless `clockwise`.
+    fn square(half: f32, center: Vec2, clockwise: bool) -> ShapePath {
+        let corners = [(-1.0, -1.0), (1.0, -1.0), (1.0, 1.0), (-1.0, 1.0)]
+            .map(|(x, y)| center + Vec2::new(x, y) * half);
+        let mut path = ShapePath::new().move_to(corners[0]);
+        let rest: Vec<_> = if clockwise {
+            corners[1..].iter().rev().collect()
+        } else {
+            corners[1..].iter().collect()
+        };
+        for corner in rest {
+            path = path.line_to(*corner);
+        }
+        path.close()
+    }
+
+    fn shape(subpaths: &[ShapePath]) -> Shape {
+        subpaths
+            .iter()
+            .fold(ShapeBuilder::new(), ShapeBuilderBase::add)
+            .fill(Fill::color(GOLD))
+            .build()
+    }
+
+    /// Returns the signed area of every subpath of `shape`.
+    fn subpath_areas(shape: &Shape) -> Vec<f32> {
+        let mut areas = Vec::new();
+        let mut events = Vec::new();
+        for event in &shape.path {
+            events.push(event);
+            if let PathEvent::End { .. } = event {
+                areas.push(approximate_signed_area(0.01, std::mem::take(&mut events)));
+            }
+        }
+        areas
+    }
+
+    #[test]
+    fn nested_rings_alternate_winding() {
+        // Three nested squares and a separate one, all counter-clockwise
+        // except the innermost.
+        let mut nested = shape(&[
+            square(10.0, Vec2::ZERO, false),
+            square(6.0, Vec2::ZERO, false),
+            square(2.0, Vec2::ZERO, true),
+            square(2.0, Vec2::new(30.0, 0.0), false),
+        ]);
+        nested.normalize_winding(Winding::Positive);
+        assert_eq!(subpath_areas(&nested), vec![400.0, -144.0, 16.0, 16.0]);
+
+        nested.normalize_winding(Winding::Negative);
+        assert_eq!(subpath_areas(&nested), vec![-400.0, 144.0, -16.0, -16.0]);
+    }
+
+    #[test]
+    fn normalization_keeps_the_order_of_subpaths() {
+        // The hole is drawn before the outline containing it.
+        let mut ring = shape(&[
+            square(5.0, Vec2::ZERO, false),
+            square(10.0, Vec2::ZERO, true),
+        ]);
+        ring.normalize_winding(Winding::Positive);
+        assert_eq!(subpath_areas(&ring), vec![-100.0, 400.0]);
+    }
+
+    #[test]
+    fn reverse_and_transform() {
+        let mut shape = shape(&[square(1.0, Vec2::ZERO, false)]);
+        shape.reverse();
+        assert_eq!(subpath_areas(&shape), vec![-4.0]);
+        shape.transform(Affine2::from_scale(Vec2::new(3.0, -1.0)));
+        assert_eq!(subpath_areas(&shape), vec![12.0]);
+    }
+}
@@ -0,0 +1,951 @@
+//! Shapes drawn as `bevy_ui` nodes.
+//!
+//! A [`ShapeNode`] lays out like an [`ImageNode`]:
+//! its shape is tessellated at the physical size of the node
+//! whenever the shape or the size of the node change,
+//! and its triangles are drawn in the UI pass, in the stacking order of the
+//! other nodes.
+//! The edges of the fill and of the stroke are anti-aliased
+//! by a band of one physical pixel whose coverage fades out.
+//!
+//! This module is available with the `ui` feature.
+
+use std::{ops::Range, sync::Arc};
+
+use bevy::{
+    asset::load_internal_asset,
+    ecs::{
+        entity::EntityHashMap,
+        system::{
+            lifetimeless::{Read, SRes},
+            SystemParamItem,
+        },
+    },
+    image::BevyDefault,
+    math::{Affine2, Affine3A, FloatOrd},
+    prelude::*,
+    render::{
+        render_phase::{
+            AddRenderCommand, DrawFunctions, PhaseItem, PhaseItemExtraIndex, RenderCommand,
+            RenderCommandResult, SetItemPipeline, TrackedRenderPass, ViewSortedRenderPhases,
+        },
+        render_resource::{
+            binding_types::uniform_buffer, BindGroup, BindGroupEntries, BindGroupLayout,
+            BindGroupLayoutEntries, BlendState, BufferUsages, ColorTargetState, ColorWrites,
+            DynamicUniformBuffer, FragmentState, IndexFormat, MultisampleState, PipelineCache,
+            PrimitiveState, RawBufferVec, RenderPipelineDescriptor, ShaderStages,
+            SpecializedRenderPipeline, SpecializedRenderPipelines, TextureFormat,
+            VertexBufferLayout, VertexFormat, VertexState, VertexStepMode,
+        },
+        renderer::{RenderDevice, RenderQueue},
+        sync_world::{MainEntity, RenderEntity, TemporaryRenderEntity},
+        view::{ExtractedView, ViewTarget, ViewUniform, ViewUniformOffset, ViewUniforms},
+        Extract, ExtractSchedule, Render, RenderApp, RenderSet,
+    },
+    sprite::Anchor,
+    ui::{
+        stack_z_offsets, CalculatedClip, ContentSize, DefaultUiCamera, RenderUiSystem,
+        TransparentUi, UiSystem,
+    },
+    utils::HashMap,
+    window::PrimaryWindow,
+};
+use uniform::ShapeNodeUniform;
+
+use crate::{
+    entity::Shape,
+    fit::FitMode,
+    gradient::Gradient,
+    plugin::{tessellate, FillTessellator, StrokeTessellator},
+    utils::ToRect,
+};
+
+const SHAPE_NODE_SHADER_HANDLE: Handle<Shader> =
+    Handle::weak_from_u128(0x2D6E_A419_73B5_4C0E_9F82_1B4D_E6A3_5C17);
+
+/// Tessellation tolerance of shape nodes, in physical pixels.
+const TOLERANCE: f32 = 0.1;
+
+/// Width of the band anti-aliasing the edges of shape nodes,
+/// in physical pixels.
+const FEATHER: f32 = 1.0;
+
+/// The longest distance a vertex of the anti-aliasing band can be moved
+/// away from a sharp corner, in multiples of [`FEATHER`].
+const MITER_LIMIT: f32 = 4.0;
+
+/// The clipping area of nodes that are not clipped.
+const NO_CLIP: Rect = Rect {
+    min: Vec2::MIN,
+    max: Vec2::MAX,
+};
+
+type SizedShapeFn = dyn Fn(Vec2) -> Shape + Send + Sync;
+
+/// `Component` drawing a [`Shape`] inside a UI node.
+///
+/// The geometry in the `view_box` (by default, the bounds of the shape,
+/// including the stroke) is fitted into the node according to `fit`
+/// and `anchor`,
+/// one unit of geometry being one logical pixel.
+/// Unless the [`Node`] has a definite size,
+/// it is sized like the view box.
+///
+/// Shapes that depend on the size of the node,
+/// like panels with rounded corners,
+/// can be created with [`ShapeNode::sized`].
+///
+/// # Example
+///
+/// ```
+/// use bevy::{color::palettes::css::*, prelude::*};
+/// use bevy_prototype_lyon::prelude::*;
+///
+/// fn setup_system(mut commands: Commands) {
+///     let panel = ShapeNode::sized(|size| {
+///         let rect = shapes::Rectangle {
+///             extents: size,
+///             radii: Some(BorderRadii::top(16.0)),
+///             ..default()
+///         };
+///         ShapeBuilder::with(&rect).fill(DARK_SLATE_GRAY).build()
+///     });
+///     commands.spawn((
+///         panel,
+///         Node {
+///             width: Val::Px(300.0),
+///             height: Val::Px(200.0),
+///             ..default()
+///         },
+///     ));
+/// }
+/// ```
+#[derive(Component, Clone)]
+#[require(Node, ContentSize, ShapeNodeMesh)]
+pub struct ShapeNode {
+    /// The shape drawn in the node.
+    ///
+    /// For sized nodes, this is the shape built for the current node size.
+    pub shape: Shape,
+    /// How the view box is fitted into the node.
+    pub fit: FitMode,
+    /// How the view box is aligned inside the node.
+    pub anchor: Anchor,
+    /// The area of the geometry fitted into the node.
+    ///
+    /// If `None`, the bounds of the shape are used.
+    /// Setting a fixed view box keeps a shape that changes over time,
+    /// like an arc showing a progress, from moving inside the node.
+    pub view_box: Option<Rect>,
+    sized: Option<Arc<SizedShapeFn>>,
+}
+
+impl ShapeNode {
+    /// Creates a node drawing `shape`, fitted with [`FitMode::Contain`].
+    #[must_use]
+    pub fn new(shape: Shape) -> Self {
+        Self {
+            shape,
+            fit: FitMode::default(),
+            anchor: Anchor::default(),
+            view_box: None,
+            sized: None,
+        }
+    }
+
+    /// Creates a node drawing the shape returned by `build`,
+    /// which is called with the logical size of the node when it changes.
+    ///
+    /// The origin of the shape is placed at the center of the node,
+    /// and its geometry is not scaled,
+    /// so `fit`, `anchor` and `view_box` are ignored.
+    #[must_use]
+    pub fn sized(build: impl Fn(Vec2) -> Shape + Send + Sync + 'static) -> Self {
+        Self {
+            shape: Shape::default(),
+            fit: FitMode::default(),
+            anchor: Anchor::default(),
+            view_box: None,
+            sized: Some(Arc::new(build)),
+        }
+    }
+
+    /// Sets how the view box is fitted into the node.
+    #[must_use]
+    pub fn with_fit(mut self, fit: FitMode) -> Self {
+        self.fit = fit;
+        self
+    }
+
+    /// Sets how the view box is aligned inside the node.
+    #[must_use]
+    pub fn with_anchor(mut self, anchor: Anchor) -> Self {
+        self.anchor = anchor;
+        self
+    }
+
+    /// Sets the area of the geometry fitted into the node.
+    #[must_use]
+    pub fn with_view_box(mut self, view_box: Rect) -> Self {
+        self.view_box = Some(view_box);
+        self
+    }
+
+    /// Returns the area of the geometry fitted into the node.
+    fn view_box(&self) -> Rect {
+        self.view_box.unwrap_or_else(|| {
+            let margin = self
+                .shape
+                .stroke
+                .map_or(0.0, |stroke| stroke.options.line_width / 2.0);
+            lyon_algorithms::aabb::bounding_box(self.shape.path.iter())
+                .to_rect()
+                .inflate(margin)
+        })
+    }
+}
+
+impl From<Shape> for ShapeNode {
+    fn from(shape: Shape) -> Self {
+        Self::new(shape)
+    }
+}
+
+/// Adds the systems and the render logic of shape nodes to the app.
+pub(crate) struct ShapeNodePlugin;
+
+impl Plugin for ShapeNodePlugin {
+    fn build(&self, app: &mut App) {
+        load_internal_asset!(app, SHAPE_NODE_SHADER_HANDLE, "ui.wgsl", Shader::from_wgsl);
+
+        app.add_systems(
+            PostUpdate,
+            (
+                content_size_system.in_set(UiSystem::Prepare),
+                mesh_shape_nodes_system.in_set(UiSystem::PostLayout),
+            ),
+        );
+
+        let Some(render_app) = app.get_sub_app_mut(RenderApp) else {
+            return;
+        };
+        render_app
+            .add_render_command::<TransparentUi, DrawShapeNode>()
+            .init_resource::<ExtractedShapeNodes>()
+            .init_resource::<ShapeNodeMeta>()
+            .init_resource::<SpecializedRenderPipelines<ShapeNodePipeline>>()
+            .add_systems(
+                ExtractSchedule,
+                extract_shape_nodes.in_set(RenderUiSystem::ExtractBackgrounds),
+            )
+            .add_systems(
+                Render,
+                (
+                    queue_shape_nodes.in_set(RenderSet::Queue),
+                    prepare_shape_nodes.in_set(RenderSet::PrepareBindGroups),
+                ),
+            );
+    }
+
+    fn finish(&self, app: &mut App) {
+        if let Some(render_app) = app.get_sub_app_mut(RenderApp) {
+            render_app.init_resource::<ShapeNodePipeline>();
+        }
+    }
+}
+
+/// Sizes the shape nodes without a definite size like their view box.
+fn content_size_system(
+    mut previous_scale_factor: Local<f32>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    ui_scale: Res<UiScale>,
+    mut query: Query<(Ref<ShapeNode>, &mut ContentSize)>,
+) {
+    let scale_factor = windows
+        .get_single()
+        .map_or(1.0, |window| window.resolution.scale_factor())
+        * ui_scale.0;
+    for (node, mut content_size) in &mut query {
+        if node.sized.is_some() {
+            continue;
+        }
+        if node.is_changed()
+            || content_size.is_added()
+            || (scale_factor - *previous_scale_factor).abs() > f32::EPSILON
+        {
+            *content_size = ContentSize::fixed_size(node.view_box().size() * scale_factor);
+        }
+    }
+    *previous_scale_factor = scale_factor;
+}
+
+/// Tessellates the shape of changed or resized shape nodes.
+fn mesh_shape_nodes_system(
+    mut fill_tess: ResMut<FillTessellator>,
+    mut stroke_tess: ResMut<StrokeTessellator>,
+    mut query: Query<(&mut ShapeNode, &ComputedNode, &mut ShapeNodeMesh)>,
+) {
+    for (mut node, computed, mut mesh) in &mut query {
+        let size = computed.size();
+        let scale_factor = computed.inverse_scale_factor().recip();
+        if !node.is_changed()
+            && mesh.size == size
+            && (mesh.scale_factor - scale_factor).abs() <= f32::EPSILON
+        {
+            continue;
+        }
+
+        let transform = if let Some(build) = node.sized.clone() {
+            node.bypass_change_detection().shape = build(size / scale_factor);
+            Affine2::from_scale(Vec2::splat(scale_factor))
+        } else {
+            node.fit.transform(
+                node.view_box(),
+                Rect::from_center_size(Vec2::ZERO, size),
+                node.anchor,
+            )
+        };
+        // UI nodes go down, while geometry goes up.
+        let transform = Affine2::from_scale(Vec2::new(1.0, -1.0)) * transform;
+
+        mesh.size = size;
+        mesh.scale_factor = scale_factor;
+        mesh.build(&node.shape, transform, &mut fill_tess, &mut stroke_tess);
+    }
+}
+
+/// The triangles of a [`ShapeNode`],
+/// in the physical pixels of the node, relative to its center.
+#[derive(Component, Default)]
+struct ShapeNodeMesh {
+    /// The size of the node the mesh was built for.
+    size: Vec2,
+    /// The scale factor of the node the mesh was built for.
+    scale_factor: f32,
+    vertices: Vec<ShapeNodeVertex>,
+    indices: Vec<u32>,
+    fill: Option<Gradient>,
+    stroke: Option<Gradient>,
+}
+
+/// A vertex of a [`ShapeNodeMesh`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct ShapeNodeVertex {
+    position: Vec2,
+    /// The position in the local space of the shape,
+    /// where gradients are evaluated.
+    local_position: Vec2,
+    color: [f32; 4],
+    /// See [`ATTRIBUTE_GRADIENT`](crate::gradient::ATTRIBUTE_GRADIENT).
+    gradient_slot: f32,
+    /// The part of the vertex covered by the shape.
+    coverage: f32,
+}
+
+impl ShapeNodeMesh {
+    /// Tessellates `shape`, transformed from local space to the space of the
+    /// node.
+    fn build(
+        &mut self,
+        shape: &Shape,
+        transform: Affine2,
+        fill_tess: &mut FillTessellator,
+        stroke_tess: &mut StrokeTessellator,
+    ) {
+        self.vertices.clear();
+        self.indices.clear();
+        self.fill = shape.fill.and_then(|fill| fill.gradient);
+        self.stroke = shape.stroke.and_then(|stroke| stroke.gradient);
+
+        let max_scale = transform
+            .matrix2
+            .x_axis
+            .length()
+            .max(transform.matrix2.y_axis.length());
+        let tolerance = TOLERANCE / max_scale.max(f32::EPSILON);
+
+        // The fill and the stroke are feathered separately,
+        // so that the stroke covers the edges of the fill.
+        if let Some(mut fill) = shape.fill {
+            fill.options.tolerance = fill.options.tolerance.min(tolerance);
+            let part = Shape::new(shape.path.clone(), Some(fill), None);
+            self.add_part(&part, transform, fill_tess, stroke_tess);
+        }
+        if let Some(mut stroke) = shape.stroke {
+            stroke.options.tolerance = stroke.options.tolerance.min(tolerance);
+            let part = Shape::new(shape.path.clone(), None, Some(stroke));
+            self.add_part(&part, transform, fill_tess, stroke_tess);
+        }
+    }
+
+    /// Adds the triangles of a shape with only a fill or a stroke.
+    fn add_part(
+        &mut self,
+        part: &Shape,
+        transform: Affine2,
+        fill_tess: &mut FillTessellator,
+        stroke_tess: &mut StrokeTessellator,
+    ) {
+        let buffers = tessellate(part, fill_tess, stroke_tess, None);
+        let first_vertex = self.vertices.len() as u32;
+        let first_index = self.indices.len();
+        self.vertices
+            .extend(buffers.vertices.iter().map(|vertex| ShapeNodeVertex {
+                position: transform.transform_point2(vertex.position.into()),
+                local_position: vertex.position.into(),
+                color: vertex.color,
+                gradient_slot: vertex.gradient_slot,
+                coverage: 1.0,
+            }));
+        self.indices
+            .extend(buffers.indices.iter().map(|index| first_vertex + index));
+        self.feather(first_vertex, first_index);
+    }
+
+    /// Surrounds the triangles from `first_index` with a band of [`FEATHER`]
+    /// pixels, whose coverage fades out from their outline.
+    fn feather(&mut self, first_vertex: u32, first_index: usize) {
+        // Sum and count of the outward normals of the outline at each vertex.
+        let mut normals = HashMap::<u32, (Vec2, f32)>::new();
+        let outline = self.outline(first_vertex, first_index);
+        for &(a, b, normal) in &outline {
+            for vertex in [a, b] {
+                let (sum, count) = normals.entry(vertex).or_default();
+                *sum += normal;
+                *count += 1.0;
+            }
+        }
+
+        // Each vertex of the outline is moved outwards along its miter,
+        // into a transparent copy.
+        let mut outer = HashMap::<u32, u32>::with_capacity(normals.len());
+        for (vertex, (sum, count)) in normals {
+            let length_squared = sum.length_squared();
+            let miter = if length_squared > f32::EPSILON {
+                (sum * count / length_squared).clamp_length_max(MITER_LIMIT)
+            } else {
+                Vec2::ZERO
+            };
+            let mut copy = self.vertices[vertex as usize];
+            copy.position += miter * FEATHER;
+            copy.coverage = 0.0;
+            outer.insert(vertex, self.vertices.len() as u32);
+            self.vertices.push(copy);
+        }
+        for (a, b, _) in outline {
+            let (outer_a, outer_b) = (outer[&a], outer[&b]);
+            self.indices.extend([a, b, outer_b, a, outer_b, outer_a]);
+        }
+    }
+
+    /// Returns the edges on the outline of the triangles from `first_index`,
+    /// with their outward normal.
+    ///
+    /// Edges used by a single triangle are on the outline,
+    /// and their normal points away from the opposite vertex of the triangle.
+    /// Vertices at the same position, like the ends of closed strokes,
+    /// are merged so that the edges between them are not on the outline.
+    fn outline(&self, first_vertex: u32, first_index: usize) -> Vec<(u32, u32, Vec2)> {
+        let mut merged = HashMap::<IVec2, u32>::new();
+        let canonical: Vec<u32> = (first_vertex..self.vertices.len() as u32)
+            .map(|i| {
+                let key = (self.vertices[i as usize].position * 256.0)
+                    .round()
+                    .as_ivec2();
+                *merged.entry(key).or_insert(i)
+            })
+            .collect();
+
+        let mut edges = HashMap::<(u32, u32), Option<u32>>::new();
+        for triangle in self.indices[first_index..].chunks_exact(3) {
+            let triangle = [0, 1, 2].map(|i| canonical[(triangle[i] - first_vertex) as usize]);
+            for i in 0..3 {
+                let (a, b) = (triangle[i], triangle[(i + 1) % 3]);
+                edges
+                    .entry((a.min(b), a.max(b)))
+                    .and_modify(|opposite| *opposite = None)
+                    .or_insert(Some(triangle[(i + 2) % 3]));
+            }
+        }
+
+        edges
+            .into_iter()
+            .filter_map(|((a, b), opposite)| {
+                let [pa, pb, pc] = [a, b, opposite?].map(|i| self.vertices[i as usize].position);
+                let normal = (pb - pa).try_normalize()?.perp();
+                Some((
+                    a,
+                    b,
+                    if normal.dot(pc - pa) > 0.0 {
+                        -normal
+                    } else {
+                        normal
+                    },
+                ))
+            })
+            .collect()
+    }
+}
+
+/// A [`ShapeNode`] extracted to the render world.
+struct ExtractedShapeNode {
+    stack_index: u32,
+    /// The vertices, with their position in the space of the UI camera.
+    vertices: Vec<[f32; 10]>,
+    indices: Vec<u32>,
+    uniform: ShapeNodeUniform,
+    camera_entity: Entity,
+    main_entity: MainEntity,
+}
+
+impl ShapeNodeVertex {
+    /// Returns the vertex as laid out in the vertex buffer,
+    /// with its position transformed by `transform`.
+    fn to_array(self, transform: &Affine3A) -> [f32; 10] {
+        let position = transform.transform_point3(self.position.extend(0.0));
+        [
+            position.x,
+            position.y,
+            self.local_position.x,
+            self.local_position.y,
+            self.color[0],
+            self.color[1],
+            self.color[2],
+            self.color[3],
+            self.gradient_slot,
+            self.coverage,
+        ]
+    }
+}
+
+#[allow(dead_code)] // False positives with `ShaderType`.
+mod uniform {
+    use bevy::{math::Vec4, render::render_resource::ShaderType};
+
+    use crate::gradient::GradientUniform;
+
+    /// The uniform data of a shape node.
+    #[derive(Clone, Default, ShaderType)]
+    pub(super) struct ShapeNodeUniform {
+        pub fill: GradientUniform,
+        pub stroke: GradientUniform,
+        /// The area outside of which the node is clipped, as `min.xy, max.xy`.
+        pub clip: Vec4,
+    }
+}
+
+#[derive(Resource, Default)]
+struct ExtractedShapeNodes(EntityHashMap<ExtractedShapeNode>);
+
+#[allow(clippy::type_complexity)]
+fn extract_shape_nodes(
+    mut commands: Commands,
+    mut extracted: ResMut<ExtractedShapeNodes>,
+    default_ui_camera: Extract<DefaultUiCamera>,
+    query: Extract<
+        Query<(
+            Entity,
+            &ComputedNode,
+            &GlobalTransform,
+            &ViewVisibility,
+            Option<&CalculatedClip>,
+            Option<&TargetCamera>,
+            &ShapeNodeMesh,
+        )>,
+    >,
+    mapping: Extract<Query<RenderEntity>>,
+) {
+    for (entity, computed, transform, view_visibility, clip, camera, mesh) in &query {
+        if !view_visibility.get() || mesh.indices.is_empty() {
+            continue;
+        }
+        let Some(camera_entity) = camera
+            .map(TargetCamera::entity)
+            .or_else(|| default_ui_camera.get())
+        else {
+            continue;
+        };
+        let Ok(camera_entity) = mapping.get(camera_entity) else {
+            continue;
+        };
+
+        let transform = transform.affine();
+        let clip = clip.map_or(NO_CLIP, |clip| clip.clip);
+        extracted.0.insert(
+            commands.spawn(TemporaryRenderEntity).id(),
+            ExtractedShapeNode {
+                stack_index: computed.stack_index(),
+                vertices: mesh
+                    .vertices
+                    .iter()
+                    .map(|vertex| vertex.to_array(&transform))
+                    .collect(),
+                indices: mesh.indices.clone(),
+                uniform: ShapeNodeUniform {
+                    fill: mesh.fill.as_ref().map(Into::into).unwrap_or_default(),
+                    stroke: mesh.stroke.as_ref().map(Into::into).unwrap_or_default(),
+                    clip: Vec4::new(clip.min.x, clip.min.y, clip.max.x, clip.max.y),
+                },
+                camera_entity,
+                main_entity: entity.into(),
+            },
+        );
+    }
+}
+
+fn queue_shape_nodes(
+    extracted: Res<ExtractedShapeNodes>,
+    draw_functions: Res<DrawFunctions<TransparentUi>>,
+    pipeline: Res<ShapeNodePipeline>,
+    mut pipelines: ResMut<SpecializedRenderPipelines<ShapeNodePipeline>>,
+    pipeline_cache: Res<PipelineCache>,
+    mut phases: ResMut<ViewSortedRenderPhases<TransparentUi>>,
+    views: Query<&ExtractedView>,
+) {
+    let draw_function = draw_functions.read().id::<DrawShapeNode>();
+    for (entity, node) in &extracted.0 {
+        let Ok(view) = views.get(node.camera_entity) else {
+            continue;
+        };
+        let Some(phase) = phases.get_mut(&node.camera_entity) else {
+            continue;
+        };
+        phase.add(TransparentUi {
+            draw_function,
+            pipeline: pipelines.specialize(
+                &pipeline_cache,
+                &pipeline,
+                ShapeNodePipelineKey { hdr: view.hdr },
+            ),
+            entity: (*entity, node.main_entity),
+            sort_key: (
+                FloatOrd(node.stack_index as f32 + stack_z_offsets::MATERIAL),
+                entity.index(),
+            ),
+            batch_range: 0..0,
+            extra_index: PhaseItemExtraIndex::NONE,
+        });
+    }
+}
+
+/// The buffers and bind groups shared by the shape nodes of a frame.
+#[derive(Resource)]
+struct ShapeNodeMeta {
+    vertices: RawBufferVec<[f32; 10]>,
+    indices: RawBufferVec<u32>,
+    uniforms: DynamicUniformBuffer<ShapeNodeUniform>,
+    view_bind_group: Option<BindGroup>,
+    node_bind_group: Option<BindGroup>,
+}
+
+impl Default for ShapeNodeMeta {
+    fn default() -> Self {
+        Self {
+            vertices: RawBufferVec::new(BufferUsages::VERTEX),
+            indices: RawBufferVec::new(BufferUsages::INDEX),
+            uniforms: DynamicUniformBuffer::default(),
+            view_bind_group: None,
+            node_bind_group: None,
+        }
+    }
+}
+
+/// The part of the buffers of [`ShapeNodeMeta`] drawing a shape node.
+#[derive(Component)]
+struct ShapeNodeBatch {
+    indices: Range<u32>,
+    uniform_offset: u32,
+}
+
+#[allow(clippy::too_many_arguments)]
+fn prepare_shape_nodes(
+    mut commands: Commands,
+    render_device: Res<RenderDevice>,
+    render_queue: Res<RenderQueue>,
+    mut meta: ResMut<ShapeNodeMeta>,
+    mut extracted: ResMut<ExtractedShapeNodes>,
+    view_uniforms: Res<ViewUniforms>,
+    pipeline: Res<ShapeNodePipeline>,
+    mut phases: ResMut<ViewSortedRenderPhases<TransparentUi>>,
+) {
+    let Some(view_binding) = view_uniforms.uniforms.binding() else {
+        extracted.0.clear();
+        return;
+    };
+
+    let meta = &mut *meta;
+    meta.vertices.clear();
+    meta.indices.clear();
+    meta.uniforms.clear();
+    let mut batches = Vec::with_capacity(extracted.0.len());
+    for phase in phases.values_mut() {
+        for item in &mut phase.items {
+            let Some(node) = extracted.0.remove(&item.entity()) else {
+                continue;
+            };
+            let first_vertex = meta.vertices.len() as u32;
+            let start = meta.indices.len() as u32;
+            meta.vertices.extend(node.vertices);
+            meta.indices
+                .extend(node.indices.into_iter().map(|index| first_vertex + index));
+            batches.push((
+                item.entity(),
+                ShapeNodeBatch {
+                    indices: start..meta.indices.len() as u32,
+                    uniform_offset: meta.uniforms.push(&node.uniform),
+                },
+            ));
+            *item.batch_range_mut() = 0..1;
+        }
+    }
+    extracted.0.clear();
+
+    meta.vertices.write_buffer(&render_device, &render_queue);
+    meta.indices.write_buffer(&render_device, &render_queue);
+    meta.uniforms.write_buffer(&render_device, &render_queue);
+    meta.view_bind_group = Some(render_device.create_bind_group(
+        "shape_node_view_bind_group",
+        &pipeline.view_layout,
+        &BindGroupEntries::single(view_binding),
+    ));
+    meta.node_bind_group = meta.uniforms.binding().map(|binding| {
+        render_device.create_bind_group(
+            "shape_node_bind_group",
+            &pipeline.node_layout,
+            &BindGroupEntries::single(binding),
+        )
+    });
+    commands.insert_or_spawn_batch(batches);
+}
+
+#[derive(Resource)]
+struct ShapeNodePipeline {
+    view_layout: BindGroupLayout,
+    node_layout: BindGroupLayout,
+}
+
+impl FromWorld for ShapeNodePipeline {
+    fn from_world(world: &mut World) -> Self {
+        let render_device = world.resource::<RenderDevice>();
+        Self {
+            view_layout: render_device.create_bind_group_layout(
+                "shape_node_view_layout",
+                &BindGroupLayoutEntries::single(
+                    ShaderStages::VERTEX_FRAGMENT,
+                    uniform_buffer::<ViewUniform>(true),
+                ),
+            ),
+            node_layout: render_device.create_bind_group_layout(
+                "shape_node_layout",
+                &BindGroupLayoutEntries::single(
+                    ShaderStages::FRAGMENT,
+                    uniform_buffer::<ShapeNodeUniform>(true),
+                ),
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct ShapeNodePipelineKey {
+    hdr: bool,
+}
+
+impl SpecializedRenderPipeline for ShapeNodePipeline {
+    type Key = ShapeNodePipelineKey;
+
+    fn specialize(&self, key: Self::Key) -> RenderPipelineDescriptor {
+        let vertex_layout = VertexBufferLayout::from_vertex_formats(
+            VertexStepMode::Vertex,
+            vec![
+                // position
+                VertexFormat::Float32x2,
+                // local_position
+                VertexFormat::Float32x2,
+                // color
+                VertexFormat::Float32x4,
+                // gradient_slot, coverage
+                VertexFormat::Float32x2,
+            ],
+        );
+
+        RenderPipelineDescriptor {
+            vertex: VertexState {
+                shader: SHAPE_NODE_SHADER_HANDLE,
+                entry_point: "vertex".into(),
+                shader_defs: Vec::new(),
+                buffers: vec![vertex_layout],
+            },
+            fragment: Some(FragmentState {
+                shader: SHAPE_NODE_SHADER_HANDLE,
+                shader_defs: Vec::new(),
+                entry_point: "fragment".into(),
+                targets: vec![Some(ColorTargetState {
+                    format: if key.hdr {
+                        ViewTarget::TEXTURE_FORMAT_HDR
+                    } else {
+                        TextureFormat::bevy_default()
+                    },
+                    blend: Some(BlendState::ALPHA_BLENDING),
+                    write_mask: ColorWrites::ALL,
+                })],
+            }),
+            layout: vec![self.view_layout.clone(), self.node_layout.clone()],
+            push_constant_ranges: Vec::new(),
+            primitive: PrimitiveState::default(),
+            depth_stencil: None,
+            multisample: MultisampleState::default(),
+            label: Some("shape_node_pipeline".into()),
+            zero_initialize_workgroup_memory: false,
+        }
+    }
+}
+
+type DrawShapeNode = (SetItemPipeline, SetShapeNodeBindGroups, DrawShapeNodeMesh);
+
+struct SetShapeNodeBindGroups;
+
+impl<P: PhaseItem> RenderCommand<P> for SetShapeNodeBindGroups {
+    type ItemQuery = Read<ShapeNodeBatch>;
+    type Param = SRes<ShapeNodeMeta>;
+    type ViewQuery = Read<ViewUniformOffset>;
+
+    fn render<'w>(
+        _item: &P,
+        view_uniform: &'w ViewUniformOffset,
+        batch: Option<&'w ShapeNodeBatch>,
+        meta: SystemParamItem<'w, '_, Self::Param>,
+        pass: &mut TrackedRenderPass<'w>,
+    ) -> RenderCommandResult {
+        let meta = meta.into_inner();
+        let (Some(batch), Some(view_bind_group), Some(node_bind_group)) = (
+            batch,
+            meta.view_bind_group.as_ref(),
+            meta.node_bind_group.as_ref(),
+        ) else {
+            return RenderCommandResult::Skip;
+        };
+        pass.set_bind_group(0, view_bind_group, &[view_uniform.offset]);
+        pass.set_bind_group(1, node_bind_group, &[batch.uniform_offset]);
+        RenderCommandResult::Success
+    }
+}
+
+struct DrawShapeNodeMesh;
+
+impl<P: PhaseItem> RenderCommand<P> for DrawShapeNodeMesh {
+    type ItemQuery = Read<ShapeNodeBatch>;
+    type Param = SRes<ShapeNodeMeta>;
+    type ViewQuery = ();
+
+    fn render<'w>(
+        _item: &P,
+        _view: (),
+        batch: Option<&'w ShapeNodeBatch>,
+        meta: SystemParamItem<'w, '_, Self::Param>,
+        pass: &mut TrackedRenderPass<'w>,
+    ) -> RenderCommandResult {
+        let meta = meta.into_inner();
+        let (Some(batch), Some(vertices), Some(indices)) =
+            (batch, meta.vertices.buffer(), meta.indices.buffer())
+        else {
+            return RenderCommandResult::Skip;
+        };
+        pass.set_vertex_buffer(0, vertices.slice(..));
+        pass.set_index_buffer(indices.slice(..), 0, IndexFormat::Uint32);
+        pass.draw_indexed(batch.indices.clone(), 0, 0..1);
+        RenderCommandResult::Success
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::prelude::*;
+
+    use super::{ShapeNodeMesh, ShapeNodeVertex, FEATHER};
+
+    /// Returns a mesh with the two triangles of a square from `(0, 0)` to
+    /// `(2, 2)`, whose vertices are shared or duplicated.
+    fn square(shared: bool) -> ShapeNodeMesh {
+        let corners = [
+            Vec2::new(0.0, 0.0),
+            Vec2::new(2.0, 0.0),
+            Vec2::new(2.0, 2.0),
+            Vec2::new(0.0, 2.0),
+        ];
+        let corners: Vec<Vec2> = if shared {
+            corners.to_vec()
+        } else {
+            [0, 1, 2, 0, 2, 3].map(|i| corners[i]).to_vec()
+        };
+        ShapeNodeMesh {
+            vertices: corners
+                .into_iter()
+                .map(|position| ShapeNodeVertex {
+                    position,
+                    local_position: position,
+                    color: [1.0; 4],
+                    gradient_slot: 0.0,
+                    coverage: 1.0,
+                })
+                .collect(),
+            indices: if shared {
+                vec![0, 1, 2, 0, 2, 3]
+            } else {
+                (0..6).collect()
+            },
+            ..default()
+        }
+    }
+
+    fn assert_feathered_square(mesh: &ShapeNodeMesh, inner: usize) {
+        let outer = &mesh.vertices[inner..];
+        assert_eq!(outer.len(), 4);
+        // Two triangles per edge of the outline.
+        assert_eq!(mesh.indices.len(), 6 + 4 * 6);
+        for vertex in outer {
+            assert!(vertex.coverage <= 0.0);
+            // Corners move diagonally, to stay `FEATHER` away from both edges.
+            let offset = vertex.position - vertex.local_position;
+            assert_eq!(offset.abs(), Vec2::splat(FEATHER));
+            assert!((vertex.local_position - Vec2::ONE).dot(offset) > 0.0);
+        }
+    }
+
+    #[test]
+    fn feather_surrounds_the_outline() {
+        let mut mesh = square(true);
+        mesh.feather(0, 0);
+        assert_feathered_square(&mesh, 4);
+        for triangle in mesh.indices[6..].chunks_exact(3) {
+            let covered = triangle
+                .iter()
+                .filter(|&&i| mesh.vertices[i as usize].coverage > 0.0)
+                .count();
+            assert!(covered == 1 || covered == 2);
+        }
+    }
+
+    #[test]
+    fn feather_merges_vertices_at_the_same_position() {
+        let mut mesh = square(false);
+        mesh.feather(0, 0);
+        assert_feathered_square(&mesh, 6);
+    }
+
+    #[test]
+    fn feather_starts_at_the_given_part() {
+        let mut mesh = square(true);
+        mesh.feather(0, 0);
+        let len = mesh.vertices.len();
+        let first_index = mesh.indices.len();
+        let mut part = square(true);
+        mesh.vertices.append(&mut part.vertices);
+        mesh.indices
+            .extend(part.indices.iter().map(|&i| i + len as u32));
+        mesh.feather(len as u32, first_index);
+        // Only the edges of the second part are feathered again.
+        assert_eq!(mesh.vertices.len(), 2 * (4 + 4));
+        assert_eq!(mesh.indices.len(), 2 * (6 + 4 * 6));
+    }
+}
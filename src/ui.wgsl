@@ -0,0 +1,54 @@
+#import bevy_render::view::View
+#import bevy_prototype_lyon::gradient::{Gradient, gradient_color}
+
+struct ShapeNode {
+    fill: Gradient,
+    stroke: Gradient,
+    clip: vec4<f32>,
+};
+
+@group(0) @binding(0) var<uniform> view: View;
+@group(1) @binding(0) var<uniform> node: ShapeNode;
+
+struct Vertex {
+    @location(0) position: vec2<f32>,
+    @location(1) local_position: vec2<f32>,
+    @location(2) color: vec4<f32>,
+    @location(3) paint: vec2<f32>,
+};
+
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) ui_position: vec2<f32>,
+    @location(1) local_position: vec2<f32>,
+    @location(2) color: vec4<f32>,
+    @location(3) coverage: f32,
+    @location(4) @interpolate(flat) slot: u32,
+};
+
+@vertex
+fn vertex(vertex: Vertex) -> VertexOutput {
+    var out: VertexOutput;
+    out.position = view.clip_from_world * vec4<f32>(vertex.position, 0.0, 1.0);
+    out.ui_position = vertex.position;
+    out.local_position = vertex.local_position;
+    out.color = vertex.color;
+    out.slot = u32(round(vertex.paint.x));
+    out.coverage = vertex.paint.y;
+    return out;
+}
+
+@fragment
+fn fragment(in: VertexOutput) -> @location(0) vec4<f32> {
+    if any(in.ui_position < node.clip.xy) || any(in.ui_position >= node.clip.zw) {
+        discard;
+    }
+
+    var color = in.color;
+    if in.slot == 1u {
+        color = gradient_color(node.fill, in.local_position);
+    } else if in.slot == 2u {
+        color = gradient_color(node.stroke, in.local_position);
+    }
+    return vec4<f32>(color.rgb, color.a * in.coverage);
+}
@@ -1,7 +1,7 @@
 //! Utility types and conversion traits.
 
-use bevy::math::Vec2;
-use lyon_tessellation::math::{Point, Vector};
+use bevy::math::{Affine2, Rect, Vec2};
+use lyon_tessellation::math::{Box2D, Point, Transform, Vector};
 
 pub trait ToPoint {
     fn to_point(self) -> Point;
@@ -11,6 +11,14 @@ pub trait ToVector {
     fn to_vector(self) -> Vector;
 }
 
+pub trait ToTransform {
+    fn to_transform(self) -> Transform;
+}
+
+pub trait ToRect {
+    fn to_rect(self) -> Rect;
+}
+
 impl ToPoint for Vec2 {
     fn to_point(self) -> Point {
         Point::new(self.x, self.y)
@@ -22,3 +30,22 @@ impl ToVector for Vec2 {
         Vector::new(self.x, self.y)
     }
 }
+
+impl ToTransform for Affine2 {
+    fn to_transform(self) -> Transform {
+        Transform::new(
+            self.matrix2.x_axis.x,
+            self.matrix2.x_axis.y,
+            self.matrix2.y_axis.x,
+            self.matrix2.y_axis.y,
+            self.translation.x,
+            self.translation.y,
+        )
+    }
+}
+
+impl ToRect for Box2D {
+    fn to_rect(self) -> Rect {
+        Rect::new(self.min.x, self.min.y, self.max.x, self.max.y)
+    }
+}
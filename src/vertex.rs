@@ -1,8 +1,29 @@
-use bevy::color::{Color, ColorToComponents};
+//! Types for writing custom vertex attributes to shape meshes.
+//!
+//! Implement [`ShapeVertexConstructor`] and add it to a shape entity
+//! with the [`ShapeVertexAttributes`] component
+//! to make extra per-vertex data available to custom materials.
+
+use std::sync::Arc;
+
+use bevy::{
+    color::{Color, ColorToComponents},
+    log::error,
+    math::{Affine2, Vec2},
+    prelude::Component,
+    render::{
+        mesh::{Mesh, MeshVertexAttribute, VertexAttributeValues},
+        render_resource::VertexFormat,
+    },
+};
 use lyon_tessellation::{
-    self as tess, FillVertex, FillVertexConstructor, StrokeVertex, StrokeVertexConstructor,
+    self as tess,
+    path::{EndpointId, IdEvent, Path},
+    FillVertex, FillVertexConstructor, Side, StrokeVertex, StrokeVertexConstructor, VertexSource,
 };
 
+use crate::draw::StrokeUvMapping;
+
 /// The index type of a Bevy [`Mesh`](bevy::render::mesh::Mesh).
 type IndexType = u32;
 /// Lyon's [`VertexBuffers`] generic data type defined for [`Vertex`].
@@ -14,30 +35,394 @@ pub type VertexBuffers = tess::VertexBuffers<Vertex, IndexType>;
 pub struct Vertex {
     pub position: [f32; 2],
     pub color: [f32; 4],
+    pub uv: [f32; 2],
+    /// See [`ATTRIBUTE_GRADIENT`](crate::gradient::ATTRIBUTE_GRADIENT).
+    pub gradient_slot: f32,
 }
 
-/// Zero-sized type used to implement various vertex construction traits from
-/// Lyon.
-pub struct VertexConstructor {
+/// Type used to implement various vertex construction traits from Lyon.
+pub struct VertexConstructor<'a> {
     pub color: Color,
+    pub gradient_slot: f32,
+    /// Transform to texture coordinates.
+    ///
+    /// It is applied to the local position of fill vertices,
+    /// and to the advancement and side (`0.0` or `1.0`) of stroke vertices,
+    /// before [`StrokeDistances::apply`] rebases them.
+    pub uv: Affine2,
+    /// Writes the custom attributes, if any, alongside each vertex.
+    pub custom: Option<&'a mut CustomAttributes>,
+    /// Records the subpath of each stroke vertex, if any.
+    pub distances: Option<&'a mut StrokeDistances>,
 }
 
 /// Enables the construction of a [`Vertex`] when using a `FillTessellator`.
-impl FillVertexConstructor<Vertex> for VertexConstructor {
-    fn new_vertex(&mut self, vertex: FillVertex) -> Vertex {
+impl FillVertexConstructor<Vertex> for VertexConstructor<'_> {
+    fn new_vertex(&mut self, mut vertex: FillVertex) -> Vertex {
+        if let Some(custom) = &mut self.custom {
+            custom.fill_vertex(&mut vertex);
+        }
         Vertex {
             position: [vertex.position().x, vertex.position().y],
             color: self.color.to_linear().to_f32_array(),
+            uv: self
+                .uv
+                .transform_point2(Vec2::new(vertex.position().x, vertex.position().y))
+                .to_array(),
+            gradient_slot: self.gradient_slot,
         }
     }
 }
 
 /// Enables the construction of a [`Vertex`] when using a `StrokeTessellator`.
-impl StrokeVertexConstructor<Vertex> for VertexConstructor {
-    fn new_vertex(&mut self, vertex: StrokeVertex) -> Vertex {
+impl StrokeVertexConstructor<Vertex> for VertexConstructor<'_> {
+    fn new_vertex(&mut self, mut vertex: StrokeVertex) -> Vertex {
+        if let Some(custom) = &mut self.custom {
+            custom.stroke_vertex(&mut vertex);
+        }
+        if let Some(distances) = &mut self.distances {
+            distances.push(&vertex);
+        }
         Vertex {
             position: [vertex.position().x, vertex.position().y],
             color: self.color.to_linear().to_f32_array(),
+            uv: self
+                .uv
+                .transform_point2(Vec2::new(
+                    vertex.advancement(),
+                    match vertex.side() {
+                        Side::Positive => 0.0,
+                        Side::Negative => 1.0,
+                    },
+                ))
+                .to_array(),
+            gradient_slot: self.gradient_slot,
+        }
+    }
+}
+
+/// Interface for writing custom attributes to the vertices of a shape mesh.
+///
+/// The constructor is called once for each vertex generated by the fill and
+/// stroke tessellators.
+/// Built-in attributes (position, color and texture coordinates)
+/// are always written.
+/// Lyon's vertex types give access to data such as the stroke advancement,
+/// normal and side, the source endpoints,
+/// and the interpolated custom attributes of the path.
+///
+/// Only attributes with `Float32`, `Float32x2`, `Float32x3` and `Float32x4`
+/// formats are supported.
+///
+/// # Implementation example
+///
+/// ```
+/// use bevy::render::{mesh::MeshVertexAttribute, render_resource::VertexFormat};
+/// use bevy_prototype_lyon::{AttributeWriter, ShapeVertexConstructor};
+/// use lyon_tessellation::{FillVertex, Side, StrokeVertex};
+///
+/// const ATTRIBUTE_EDGE: MeshVertexAttribute =
+///     MeshVertexAttribute::new("Vertex_Edge", 0x6E5D_2A01, VertexFormat::Float32x2);
+///
+/// /// Writes the stroke advancement and side, to draw glowing edges.
+/// struct EdgeAttributes;
+///
+/// impl ShapeVertexConstructor for EdgeAttributes {
+///     fn attributes(&self) -> Vec<MeshVertexAttribute> {
+///         vec![ATTRIBUTE_EDGE]
+///     }
+///
+///     fn fill_vertex(&self, _vertex: &mut FillVertex, _writer: &mut AttributeWriter) {
+///         // Fill vertices keep the default value of zero.
+///     }
+///
+///     fn stroke_vertex(&self, vertex: &mut StrokeVertex, writer: &mut AttributeWriter) {
+///         let side = match vertex.side() {
+///             Side::Positive => -1.0,
+///             Side::Negative => 1.0,
+///         };
+///         writer.set(0, &[vertex.advancement(), side]);
+///     }
+/// }
+/// ```
+pub trait ShapeVertexConstructor: Send + Sync + 'static {
+    /// Returns the attributes written by this constructor.
+    ///
+    /// The position of each attribute in the returned list is the index used
+    /// with [`AttributeWriter::set`].
+    fn attributes(&self) -> Vec<MeshVertexAttribute>;
+
+    /// Writes the attributes of a vertex generated by the fill tessellator.
+    fn fill_vertex(&self, vertex: &mut FillVertex, writer: &mut AttributeWriter);
+
+    /// Writes the attributes of a vertex generated by the stroke tessellator.
+    fn stroke_vertex(&self, vertex: &mut StrokeVertex, writer: &mut AttributeWriter);
+}
+
+/// `Component` adding custom vertex attributes to the mesh of a
+/// [`Shape`](crate::entity::Shape).
+#[derive(Component, Clone)]
+pub struct ShapeVertexAttributes(pub Arc<dyn ShapeVertexConstructor>);
+
+impl ShapeVertexAttributes {
+    /// Creates the component from a [`ShapeVertexConstructor`].
+    pub fn new(constructor: impl ShapeVertexConstructor) -> Self {
+        Self(Arc::new(constructor))
+    }
+}
+
+/// Sets the values of the custom attributes of a vertex.
+///
+/// Attributes that are not set default to zero.
+pub struct AttributeWriter<'a> {
+    buffers: &'a mut [AttributeBuffer],
+    subpaths: &'a [usize],
+}
+
+impl AttributeWriter<'_> {
+    /// Sets the value of the attribute at position `index` in
+    /// [`ShapeVertexConstructor::attributes`].
+    ///
+    /// Extra components are ignored, missing ones are set to zero.
+    pub fn set(&mut self, index: usize, value: &[f32]) {
+        let Some(buffer) = self.buffers.get_mut(index) else {
+            return;
+        };
+        let start = buffer.values.len() - buffer.size;
+        for (slot, v) in buffer.values[start..].iter_mut().zip(value) {
+            *slot = *v;
+        }
+    }
+
+    /// Returns the index of the subpath the given endpoint belongs to.
+    ///
+    /// Endpoints can be obtained from the sources of a vertex.
+    /// They belong to the path being tessellated,
+    /// which is the trimmed path for strokes (see [`Trim`](crate::draw::Trim)).
+    #[must_use]
+    pub fn subpath(&self, endpoint: EndpointId) -> usize {
+        subpath_index(self.subpaths, endpoint)
+    }
+}
+
+struct AttributeBuffer {
+    attribute: MeshVertexAttribute,
+    /// Number of components of the attribute, or zero if unsupported.
+    size: usize,
+    values: Vec<f32>,
+}
+
+/// Values of the custom attributes of a mesh, being built.
+///
+/// Each tessellation is wrapped between [`begin`](Self::begin) and
+/// [`end`](Self::end), so that the values written during a failed
+/// tessellation are discarded along with its vertices.
+pub struct CustomAttributes {
+    constructor: Arc<dyn ShapeVertexConstructor>,
+    buffers: Vec<AttributeBuffer>,
+    /// First endpoint of each subpath of the path being tessellated.
+    subpaths: Vec<usize>,
+    /// Number of values of each buffer before the current tessellation.
+    lengths: Vec<usize>,
+}
+
+impl CustomAttributes {
+    pub fn new(constructor: &Arc<dyn ShapeVertexConstructor>) -> Self {
+        let buffers = constructor
+            .attributes()
+            .into_iter()
+            .map(|attribute| {
+                let size = match attribute.format {
+                    VertexFormat::Float32 => 1,
+                    VertexFormat::Float32x2 => 2,
+                    VertexFormat::Float32x3 => 3,
+                    VertexFormat::Float32x4 => 4,
+                    format => {
+                        error!(
+                            "Unsupported format {:?} for custom vertex attribute {}",
+                            format, attribute.name
+                        );
+                        0
+                    }
+                };
+                AttributeBuffer {
+                    attribute,
+                    size,
+                    values: Vec::new(),
+                }
+            })
+            .collect();
+        Self {
+            constructor: constructor.clone(),
+            buffers,
+            subpaths: Vec::new(),
+            lengths: Vec::new(),
+        }
+    }
+
+    /// Starts a tessellation of `path`.
+    ///
+    /// The path must be tessellated with its own endpoint ids,
+    /// for [`AttributeWriter::subpath`] to match them.
+    pub fn begin(&mut self, path: &Path) {
+        self.subpaths = subpath_starts(path);
+        self.lengths = self.buffers.iter().map(|b| b.values.len()).collect();
+    }
+
+    /// Ends the current tessellation,
+    /// discarding its values if it failed.
+    pub fn end(&mut self, success: bool) {
+        if !success {
+            for (buffer, length) in self.buffers.iter_mut().zip(&self.lengths) {
+                buffer.values.truncate(*length);
+            }
+        }
+    }
+
+    fn push_defaults(&mut self) {
+        for buffer in &mut self.buffers {
+            buffer.values.extend(std::iter::repeat_n(0.0, buffer.size));
+        }
+    }
+
+    fn fill_vertex(&mut self, vertex: &mut FillVertex) {
+        self.push_defaults();
+        let mut writer = AttributeWriter {
+            buffers: &mut self.buffers,
+            subpaths: &self.subpaths,
+        };
+        self.constructor.fill_vertex(vertex, &mut writer);
+    }
+
+    fn stroke_vertex(&mut self, vertex: &mut StrokeVertex) {
+        self.push_defaults();
+        let mut writer = AttributeWriter {
+            buffers: &mut self.buffers,
+            subpaths: &self.subpaths,
+        };
+        self.constructor.stroke_vertex(vertex, &mut writer);
+    }
+
+    /// Inserts the attributes into the mesh.
+    pub fn insert_into(self, mesh: &mut Mesh) {
+        for buffer in self.buffers {
+            debug_assert_eq!(
+                buffer.values.len(),
+                mesh.count_vertices() * buffer.size,
+                "Custom vertex attribute {} does not match the vertices",
+                buffer.attribute.name
+            );
+            let values = match buffer.size {
+                1 => VertexAttributeValues::Float32(buffer.values),
+                2 => VertexAttributeValues::Float32x2(
+                    buffer
+                        .values
+                        .chunks_exact(2)
+                        .map(|c| [c[0], c[1]])
+                        .collect(),
+                ),
+                3 => VertexAttributeValues::Float32x3(
+                    buffer
+                        .values
+                        .chunks_exact(3)
+                        .map(|c| [c[0], c[1], c[2]])
+                        .collect(),
+                ),
+                4 => VertexAttributeValues::Float32x4(
+                    buffer
+                        .values
+                        .chunks_exact(4)
+                        .map(|c| [c[0], c[1], c[2], c[3]])
+                        .collect(),
+                ),
+                _ => continue,
+            };
+            mesh.insert_attribute(buffer.attribute, values);
+        }
+    }
+}
+
+/// Returns the first endpoint of each subpath of `path`.
+fn subpath_starts(path: &Path) -> Vec<usize> {
+    path.id_iter()
+        .filter_map(|event| match event {
+            IdEvent::Begin { at } => Some(at.to_usize()),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Returns the index of the subpath `endpoint` belongs to,
+/// given the first endpoint of each subpath.
+fn subpath_index(starts: &[usize], endpoint: EndpointId) -> usize {
+    starts
+        .partition_point(|start| *start <= endpoint.to_usize())
+        .saturating_sub(1)
+}
+
+/// Distances along a stroked path, continuous across its subpaths.
+///
+/// Lyon restarts the advancement of stroke vertices from zero after closed
+/// subpaths, but not after open ones.
+/// The subpath of each vertex is recorded during tessellation,
+/// then the advancement of the vertices is rebased so that each subpath
+/// starts where the previous one ended.
+pub struct StrokeDistances {
+    /// First endpoint of each subpath of the tessellated path.
+    starts: Vec<usize>,
+    /// Subpath of each vertex.
+    subpaths: Vec<usize>,
+}
+
+impl StrokeDistances {
+    pub fn new(path: &Path) -> Self {
+        Self {
+            starts: subpath_starts(path),
+            subpaths: Vec::new(),
+        }
+    }
+
+    fn push(&mut self, vertex: &StrokeVertex) {
+        let endpoint = match vertex.source() {
+            VertexSource::Endpoint { id } => id,
+            VertexSource::Edge { from, .. } => from,
+        };
+        self.subpaths.push(subpath_index(&self.starts, endpoint));
+    }
+
+    /// Sets the texture coordinates of `vertices`,
+    /// whose `u` coordinate holds the advancement computed by lyon.
+    ///
+    /// Vertices discarded by a failed tessellation are ignored.
+    pub fn apply(mut self, vertices: &mut [Vertex], mapping: StrokeUvMapping) {
+        self.subpaths.truncate(vertices.len());
+        let mut ranges = vec![(f32::INFINITY, f32::NEG_INFINITY); self.starts.len()];
+        for (vertex, subpath) in vertices.iter().zip(&self.subpaths) {
+            let (min, max) = &mut ranges[*subpath];
+            *min = min.min(vertex.uv[0]);
+            *max = max.max(vertex.uv[0]);
+        }
+        // Offset from the advancement of each vertex to the distance along the
+        // whole path.
+        let mut total = 0.0;
+        let offsets: Vec<f32> = ranges
+            .iter()
+            .map(|(min, max)| {
+                if min > max {
+                    return 0.0;
+                }
+                let offset = total - min;
+                total += max - min;
+                offset
+            })
+            .collect();
+
+        let affine = mapping.to_affine(total);
+        for (vertex, subpath) in vertices.iter_mut().zip(&self.subpaths) {
+            let distance = vertex.uv[0] + offsets[*subpath];
+            vertex.uv = affine
+                .transform_point2(Vec2::new(distance, vertex.uv[1]))
+                .to_array();
         }
     }
 }